@@ -0,0 +1,305 @@
+//! Derive macros for the `owned` crate.
+//!
+//! Use these via the `derive` feature of `owned` rather than depending on this crate directly.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, Member, Type};
+
+/// Derives `IntoOwned` for a `#[repr(C)]` struct whose last field is a slice or `str`.
+///
+/// The owned form is `Box<Self>`: the value is moved into a new, exactly sized, allocation.
+///
+/// A sized `<Name>Owned` struct is generated too, with the same fields except that the tail is a
+/// `Vec` or `String`. `<Name>Owned::take_from()` moves a value into it from anything that can take
+/// one. `#[repr(C)]` is required as that's the only way to construct such structs, by casting from
+/// a sized struct with the same layout.
+#[proc_macro_derive(IntoOwned)]
+pub fn derive_into_owned(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_into_owned(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+enum Tail<'a> {
+    Slice(&'a Type),
+    Str,
+}
+
+fn expand_into_owned(input: DeriveInput) -> Result<TokenStream2, Error> {
+    if !has_repr(&input, "C")? {
+        return Err(Error::new_spanned(&input.ident, "IntoOwned can only be derived for #[repr(C)] structs"));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new_spanned(&input.ident, "IntoOwned can only be derived for structs")),
+    };
+
+    let members: Vec<Member> = fields.iter().enumerate().map(|(i, f)| match &f.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::from(i),
+    }).collect();
+
+    let (tail_field, tail_ty) = match fields.iter().next_back().map(|f| (f, slice_tail(&f.ty))) {
+        Some((field, Some(tail_ty))) => (field, tail_ty),
+        _ => return Err(Error::new_spanned(&input.ident,
+                                           "IntoOwned can only be derived for structs whose last field is a slice or str")),
+    };
+    let tail = members.last().unwrap();
+    let head = &members[.. members.len() - 1];
+    let head_fields: Vec<_> = fields.iter().take(head.len()).collect();
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let owned_name = format_ident!("{}Owned", name);
+    let (owned_tail_ty, tail_elem, into_tail) = match tail_ty {
+        Tail::Slice(elem) => (quote!(::owned::__private::Vec<#elem>), quote!(#elem), quote!(tail)),
+        Tail::Str => (quote!(::owned::__private::String), quote!(u8),
+                      quote!(::owned::__private::String::from_utf8_unchecked(tail))),
+    };
+
+    let tail_vis = &tail_field.vis;
+    let head_vis = head_fields.iter().map(|f| &f.vis);
+    let head_tys = head_fields.iter().map(|f| &f.ty);
+    let owned_def = match fields {
+        Fields::Named(_) => {
+            let head_idents = head_fields.iter().map(|f| &f.ident);
+            let tail_ident = &tail_field.ident;
+            quote! {
+                #vis struct #owned_name #generics #where_clause {
+                    #( #head_vis #head_idents: #head_tys, )*
+                    #tail_vis #tail_ident: #owned_tail_ty,
+                }
+            }
+        }
+        _ => quote! {
+            #vis struct #owned_name #generics ( #( #head_vis #head_tys, )* #tail_vis #owned_tail_ty ) #where_clause;
+        },
+    };
+    let owned_doc = format!("The owned form of `{}`, with the tail moved into a `{}`.", name,
+                            match tail_ty { Tail::Slice(_) => "Vec", Tail::Str => "String" });
+    let take_from_doc = format!("Takes ownership of a `{}`, moving its tail into a new allocation.", name);
+
+    Ok(quote! {
+        unsafe impl #impl_generics ::owned::IntoOwned for #name #ty_generics #where_clause {
+            type Owned = ::owned::__private::Box<Self>;
+
+            unsafe fn into_owned_unchecked(this: &mut ::core::mem::ManuallyDrop<Self>) -> Self::Owned {
+                use ::owned::__private::{alloc, handle_alloc_error, Layout};
+
+                // The pointer metadata of a slice-tailed struct is the length of its tail.
+                let len = this.#tail.len();
+                let layout = Layout::for_value::<Self>(&**this);
+
+                let dst = if layout.size() == 0 {
+                    ::core::ptr::null_mut::<u8>().wrapping_add(layout.align())
+                } else {
+                    let dst = alloc(layout);
+                    if dst.is_null() {
+                        handle_alloc_error(layout);
+                    }
+                    dst
+                };
+
+                let src = this as *mut ::core::mem::ManuallyDrop<Self> as *mut u8;
+                ::core::ptr::copy_nonoverlapping(src, dst, layout.size());
+
+                let dst = ::core::ptr::slice_from_raw_parts_mut(dst, len) as *mut Self;
+                ::owned::__private::Box::from_raw(dst)
+            }
+
+            fn box_into_owned(this: ::owned::__private::Box<Self>) -> Self::Owned {
+                this
+            }
+        }
+
+        #[doc = #owned_doc]
+        #owned_def
+
+        impl #impl_generics #owned_name #ty_generics #where_clause {
+            #[doc = #take_from_doc]
+            pub fn take_from<__C: ::owned::Take<#name #ty_generics>>(src: __C) -> Self {
+                ::owned::Take::<#name #ty_generics>::take_unsized(src, |slot| unsafe {
+                    let this: &mut #name #ty_generics = &mut **::owned::TakeSlot::into_raw(slot);
+
+                    let len = this.#tail.len();
+                    let mut tail = ::owned::__private::Vec::<#tail_elem>::with_capacity(len);
+                    ::core::ptr::copy_nonoverlapping(this.#tail.as_ptr(), tail.as_mut_ptr(), len);
+                    tail.set_len(len);
+
+                    Self {
+                        #( #head: ::core::ptr::read(&this.#head), )*
+                        #tail: #into_tail,
+                    }
+                })
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<::owned::__private::Box<#name #ty_generics>> for #owned_name #ty_generics #where_clause {
+            fn from(boxed: ::owned::__private::Box<#name #ty_generics>) -> Self {
+                Self::take_from(boxed)
+            }
+        }
+    })
+}
+
+fn slice_tail(ty: &Type) -> Option<Tail<'_>> {
+    match ty {
+        Type::Slice(slice) => Some(Tail::Slice(&slice.elem)),
+        Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => Some(Tail::Str),
+        Type::Group(group) => slice_tail(&group.elem),
+        Type::Paren(paren) => slice_tail(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Returns true if the input has a `#[repr(...)]` attribute including `repr`.
+fn has_repr(input: &DeriveInput, repr: &str) -> Result<bool, Error> {
+    let mut found = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(repr) {
+                found = true;
+            } else if meta.input.peek(syn::token::Paren) {
+                // Skip the arguments of eg align(8).
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<TokenStream2>()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(found)
+}
+
+/// Derives `DerefTake` for a `#[repr(transparent)]` newtype around a `DerefTake` container, such as
+/// `struct Ast(Box<Node>)`.
+///
+/// The newtype must implement `Deref` with the same target as the container.
+#[proc_macro_derive(DerefTake)]
+pub fn derive_deref_take(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_deref_take(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_deref_take(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let (field, field_ty) = newtype_field(&input, "DerefTake")?;
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.push(parse_quote! {
+        #field_ty: ::owned::DerefTake<Target = <Self as ::core::ops::Deref>::Target>
+    });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        unsafe impl #impl_generics ::owned::DerefTake for #name #ty_generics #where_clause {
+            fn deref_take(self) -> <Self::Target as ::owned::IntoOwned>::Owned
+                where Self::Target: ::owned::IntoOwned
+            {
+                ::owned::DerefTake::deref_take(self.#field)
+            }
+
+            fn deref_take_as<__O>(self) -> __O
+                where Self::Target: ::owned::IntoOwnedAs<__O>
+            {
+                ::owned::DerefTake::deref_take_as(self.#field)
+            }
+
+            fn deref_take_unsized<__F, __R>(self, f: __F) -> __R
+                where __F: ::core::ops::FnOnce(::owned::TakeSlot<'_, Self::Target>) -> __R
+            {
+                ::owned::DerefTake::deref_take_unsized(self.#field, f)
+            }
+        }
+    })
+}
+
+/// Derives `Take` for a `#[repr(transparent)]` newtype, taking whatever its field can be taken as.
+///
+/// `TakeOrClone` and `TryTake` are implemented too, as there are no blanket impls of them over
+/// `Take`.
+///
+/// The field's type can't depend on the newtype's type parameters, as then the impl would overlap
+/// with `impl<T> Take<T> for T`. Derive `DerefTake` for such newtypes instead.
+#[proc_macro_derive(Take)]
+pub fn derive_take(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_take(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_take(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let (field, field_ty) = newtype_field(&input, "Take")?;
+
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!(__T: ?::core::marker::Sized));
+    generics.make_where_clause().predicates.push(parse_quote! {
+        #field_ty: ::owned::Take<__T>
+    });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let params = &generics.params;
+    let predicates = where_clause.map(|w| &w.predicates);
+
+    Ok(quote! {
+        ::owned::__private::impl_take_or_clone!([#params] #name #ty_generics => __T where #predicates);
+        ::owned::__private::impl_try_take!([#params] #name #ty_generics => __T where #predicates);
+
+        unsafe impl #impl_generics ::owned::Take<__T> for #name #ty_generics #where_clause {
+            fn take_owned(self) -> <__T as ::owned::IntoOwned>::Owned
+                where __T: ::owned::IntoOwned
+            {
+                ::owned::Take::<__T>::take_owned(self.#field)
+            }
+
+            fn take_owned_as<__O>(self) -> __O
+                where __T: ::owned::IntoOwnedAs<__O>
+            {
+                ::owned::Take::<__T>::take_owned_as(self.#field)
+            }
+
+            fn take_unsized<__F, __R>(self, f: __F) -> __R
+                where __F: ::core::ops::FnOnce(::owned::TakeSlot<'_, __T>) -> __R
+            {
+                ::owned::Take::<__T>::take_unsized(self.#field, f)
+            }
+        }
+    })
+}
+
+/// Returns the field of a single-field `#[repr(transparent)]` struct.
+fn newtype_field<'a>(input: &'a DeriveInput, derive: &str) -> Result<(Member, &'a Type), Error> {
+    let msg = format!("{} can only be derived for #[repr(transparent)] structs with a single field", derive);
+
+    let transparent = has_repr(input, "transparent")?;
+
+    let fields = match &input.data {
+        Data::Struct(data) if transparent => &data.fields,
+        _ => return Err(Error::new_spanned(&input.ident, msg)),
+    };
+
+    let mut iter = fields.iter();
+    match (iter.next(), iter.next()) {
+        (Some(field), None) => {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::from(0),
+            };
+            Ok((member, &field.ty))
+        }
+        _ => Err(Error::new_spanned(&input.ident, msg)),
+    }
+}
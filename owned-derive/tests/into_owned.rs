@@ -0,0 +1,118 @@
+use std::ptr;
+use std::rc::Rc;
+
+use owned::{DynIntoOwned, IntoOwned, Take, TryDerefTake};
+
+use dropcheck::{DropCheck, DropToken};
+
+#[derive(Debug, PartialEq, Eq)]
+struct Header {
+    kind: u8,
+    seq: u32,
+}
+
+#[derive(IntoOwned)]
+#[repr(C)]
+struct Packet {
+    header: Header,
+    payload: [u8],
+}
+
+#[derive(IntoOwned)]
+#[repr(C)]
+struct Named(u64, str);
+
+#[derive(IntoOwned)]
+#[repr(C)]
+struct Tokens<T> {
+    first: T,
+    rest: [T],
+}
+
+// Sized versions of the above used to construct the DSTs by casting.
+#[repr(C)]
+struct SizedPacket<const N: usize> {
+    header: Header,
+    payload: [u8; N],
+}
+
+#[repr(C)]
+struct SizedNamed<const N: usize>(u64, [u8; N]);
+
+#[repr(C)]
+struct SizedTokens<T, const N: usize>(T, [T; N]);
+
+#[test]
+fn packet() {
+    let raw = Box::into_raw(Box::new(SizedPacket { header: Header { kind: 1, seq: 42 }, payload: *b"abc" }));
+    let boxed = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(raw as *mut u8, 3) as *mut Packet) };
+
+    let owned: Box<Packet> = Take::<Packet>::take_owned(boxed);
+    assert_eq!(owned.header, Header { kind: 1, seq: 42 });
+    assert_eq!(&owned.payload, b"abc");
+
+    let rc: Rc<Packet> = Rc::from(owned);
+    let owned = rc.try_deref_take().ok().unwrap();
+    assert_eq!(&owned.payload, b"abc");
+
+    fn take_dyn<T: ?Sized + DynIntoOwned>(b: Box<T>) -> Box<T> {
+        Take::<T>::take_owned(b)
+    }
+    assert_eq!(take_dyn(owned).header.seq, 42);
+}
+
+#[test]
+fn str_tail() {
+    let raw = Box::into_raw(Box::new(SizedNamed(7, *b"hello")));
+    let named = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(raw as *mut u8, 5) as *mut Named) };
+
+    let owned = Take::<Named>::take_owned(named);
+    assert_eq!(owned.0, 7);
+    assert_eq!(&owned.1, "hello");
+}
+
+#[test]
+fn generic_tail() {
+    let check = DropCheck::new();
+
+    let raw = Box::into_raw(Box::new(SizedTokens(check.token(), [check.token(), check.token()])));
+    let boxed = unsafe {
+        Box::from_raw(ptr::slice_from_raw_parts_mut(raw as *mut DropToken, 2) as *mut Tokens<DropToken>)
+    };
+    let rc: Rc<Tokens<DropToken>> = Rc::from(boxed);
+
+    let owned = rc.try_deref_take().ok().unwrap();
+    assert!(check.none_dropped());
+    let _first = &owned.first;
+    assert_eq!(owned.rest.len(), 2);
+
+    drop(owned);
+    assert!(check.all_dropped());
+}
+
+#[test]
+fn owned_structs() {
+    let check = DropCheck::new();
+
+    let raw = Box::into_raw(Box::new(SizedPacket { header: Header { kind: 2, seq: 7 }, payload: *b"xyz" }));
+    let boxed = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(raw as *mut u8, 3) as *mut Packet) };
+    let packet = PacketOwned::from(boxed);
+    assert_eq!(packet.header, Header { kind: 2, seq: 7 });
+    assert_eq!(packet.payload, b"xyz");
+
+    let raw = Box::into_raw(Box::new(SizedNamed(9, *b"name")));
+    let named = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(raw as *mut u8, 4) as *mut Named) };
+    let NamedOwned(n, s) = NamedOwned::take_from(named);
+    assert_eq!((n, s.as_str()), (9, "name"));
+
+    let raw = Box::into_raw(Box::new(SizedTokens(check.token(), [check.token(), check.token()])));
+    let boxed = unsafe {
+        Box::from_raw(ptr::slice_from_raw_parts_mut(raw as *mut DropToken, 2) as *mut Tokens<DropToken>)
+    };
+    let tokens = TokensOwned::take_from(boxed);
+    assert_eq!(tokens.rest.len(), 2);
+    assert!(check.none_dropped());
+    let _first = &tokens.first;
+    drop(tokens);
+    assert!(check.all_dropped());
+}
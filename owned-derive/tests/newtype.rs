@@ -0,0 +1,96 @@
+use std::ops::Deref;
+use std::rc::Rc;
+
+use owned::{DerefTake, Take, TakeOrClone, TryTake};
+
+use dropcheck::{DropCheck, DropToken};
+
+#[derive(Debug, PartialEq)]
+struct Node(u32);
+
+#[derive(DerefTake, Take)]
+#[repr(transparent)]
+struct Ast(Box<Node>);
+
+impl Deref for Ast {
+    type Target = Node;
+
+    fn deref(&self) -> &Node {
+        &self.0
+    }
+}
+
+#[derive(DerefTake, Take)]
+#[repr(transparent)]
+struct Bytes {
+    inner: Vec<u8>,
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+#[derive(DerefTake)]
+#[repr(transparent)]
+struct Shared<T>(Rc<T>);
+
+impl<T> Deref for Shared<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[derive(Take)]
+#[repr(transparent)]
+struct Token(Box<DropToken>);
+
+#[test]
+fn deref_take() {
+    assert_eq!(Ast(Box::new(Node(1))).deref_take(), Node(1));
+
+    let bytes = Bytes { inner: vec![1, 2, 3] };
+    assert_eq!(bytes.deref_take(), [1, 2, 3]);
+
+    let rc = Rc::new(String::from("shared"));
+    assert_eq!(Shared(Rc::clone(&rc)).deref_take(), "shared");
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn take() {
+    let check = DropCheck::new();
+
+    let (token, state) = check.pair();
+    let token = Take::<DropToken>::take_sized(Token(Box::new(token)));
+    assert!(state.is_not_dropped());
+    drop(token);
+    assert!(state.is_dropped());
+
+    let node: Node = Ast(Box::new(Node(2))).take_sized();
+    assert_eq!(node, Node(2));
+
+    let len = Take::<[u8]>::take_unsized(Bytes { inner: vec![4, 5] }, |slot| slot.len());
+    assert_eq!(len, 2);
+}
+
+#[test]
+fn take_or_clone() {
+    fn take_or_clone_bytes(src: impl TakeOrClone<[u8]>) -> Vec<u8> {
+        src.take_or_clone()
+    }
+
+    assert_eq!(take_or_clone_bytes(Bytes { inner: vec![1, 2] }), [1, 2]);
+    assert_eq!(take_or_clone_bytes(&[3u8, 4][..]), [3, 4]);
+}
+
+#[test]
+fn try_take() {
+    let r = TryTake::<[u8]>::try_take_owned(Bytes { inner: vec![1, 2] });
+    assert_eq!(r.unwrap(), [1, 2]);
+}
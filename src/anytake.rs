@@ -0,0 +1,93 @@
+use core::any::Any;
+
+use alloc::{
+    boxed::Box,
+    rc::Rc,
+    sync::Arc,
+};
+
+use super::*;
+
+/// Downcasting and taking ownership in one step.
+pub trait AnyTakeExt : Sized {
+    /// Takes ownership of the value if it is of type `T`, returning the container otherwise.
+    ///
+    /// Shared containers such as `Rc` are also returned if they aren't the only owner.
+    fn take_downcast<T: Any>(self) -> Result<T, Self>;
+}
+
+impl AnyTakeExt for Box<dyn Any> {
+    fn take_downcast<T: Any>(self) -> Result<T, Self> {
+        self.downcast::<T>().map(|boxed| boxed.deref_take())
+    }
+}
+
+impl AnyTakeExt for Box<dyn Any + Send> {
+    fn take_downcast<T: Any>(self) -> Result<T, Self> {
+        self.downcast::<T>().map(|boxed| boxed.deref_take())
+    }
+}
+
+impl AnyTakeExt for Box<dyn Any + Send + Sync> {
+    fn take_downcast<T: Any>(self) -> Result<T, Self> {
+        self.downcast::<T>().map(|boxed| boxed.deref_take())
+    }
+}
+
+impl AnyTakeExt for Rc<dyn Any> {
+    fn take_downcast<T: Any>(self) -> Result<T, Self> {
+        self.downcast::<T>()?
+            .try_deref_take()
+            .map_err(|rc| rc as Rc<dyn Any>)
+    }
+}
+
+impl AnyTakeExt for Arc<dyn Any + Send + Sync> {
+    fn take_downcast<T: Any>(mut self) -> Result<T, Self> {
+        // Arc::downcast() requires T: Send + Sync, which doesn't fit the trait. But the T being
+        // taken must have been Send + Sync to be put in the Arc in the first place.
+        match Arc::get_mut(&mut self) {
+            Some(any) if any.is::<T>() => {}
+            _ => return Err(self),
+        }
+
+        let this: Arc<T> = unsafe { Arc::from_raw(Arc::into_raw(self) as *const T) };
+        match this.try_deref_take() {
+            Ok(taken) => Ok(taken),
+            Err(_) => unreachable!("Arc was unique"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_box() {
+        let boxed: Box<dyn Any> = Box::new(String::from("hello"));
+        let boxed = boxed.take_downcast::<u8>().unwrap_err();
+        assert_eq!(boxed.take_downcast::<String>().unwrap(), "hello");
+
+        let boxed: Box<dyn Any + Send> = Box::new(42u8);
+        assert_eq!(boxed.take_downcast::<u8>().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_rc() {
+        let rc: Rc<dyn Any> = Rc::new(String::from("hello"));
+        let rc2 = Rc::clone(&rc);
+
+        let rc = rc.take_downcast::<String>().unwrap_err();
+        drop(rc2);
+        let rc = rc.take_downcast::<u8>().unwrap_err();
+        assert_eq!(rc.take_downcast::<String>().unwrap(), "hello");
+
+        let arc: Arc<dyn Any + Send + Sync> = Arc::new(String::from("hello"));
+        let arc2 = Arc::clone(&arc);
+        let arc = arc.take_downcast::<String>().unwrap_err();
+        drop(arc2);
+        let arc = arc.take_downcast::<u8>().unwrap_err();
+        assert_eq!(arc.take_downcast::<String>().unwrap(), "hello");
+    }
+}
@@ -0,0 +1,108 @@
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::borrow::ToOwned;
+
+use super::*;
+
+/// A reference that implements `Take<T>` by cloning.
+///
+/// For passing borrowed values to APIs that take ownership generically.
+pub struct Cloned<'a, T: Clone>(pub &'a T);
+
+impl<T: Clone> Clone for Cloned<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Clone> Copy for Cloned<'_, T> {}
+
+impl<T: Clone + fmt::Debug> fmt::Debug for Cloned<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Cloned").field(&self.0).finish()
+    }
+}
+
+unsafe impl<T: Clone> Take<T> for Cloned<'_, T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.0.clone().take_unsized(f)
+    }
+}
+
+/// A reference that implements `Take<T>` by converting to the owned form with `ToOwned`.
+///
+/// Like `Cloned`, but also for unsized types such as `str` and slices, whose owned forms are
+/// taken from in turn.
+#[cfg(feature = "alloc")]
+pub struct ClonedUnsized<'a, T: ?Sized + ToOwned>(pub &'a T);
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized + ToOwned> Clone for ClonedUnsized<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized + ToOwned> Copy for ClonedUnsized<'_, T> {}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized + ToOwned + fmt::Debug> fmt::Debug for ClonedUnsized<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ClonedUnsized").field(&self.0).finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> Take<T> for ClonedUnsized<'_, T>
+    where T: ?Sized + ToOwned,
+          T::Owned: Take<T>
+{
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.0.to_owned().take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+crate::takeorclone::impl_take_or_clone!(['a, T: Clone] Cloned<'a, T> => T);
+#[cfg(feature = "alloc")]
+crate::takeorclone::impl_take_or_clone!(['a, T: ?Sized + ToOwned] ClonedUnsized<'a, T> => T);
+crate::fallible::impl_try_take!(['a, T: Clone] Cloned<'a, T> => T);
+#[cfg(feature = "alloc")]
+crate::fallible::impl_try_take!(['a, T: ?Sized + ToOwned] ClonedUnsized<'a, T> => T);
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use alloc::{string::String, vec::Vec};
+
+    fn take_string(src: impl Take<String>) -> String {
+        src.take_sized()
+    }
+
+    fn take_str(src: impl Take<str>) -> String {
+        src.take_owned()
+    }
+
+    #[test]
+    fn test_cloned() {
+        let s = String::from("borrowed");
+        assert_eq!(take_string(Cloned(&s)), "borrowed");
+        assert_eq!(take_string(s), "borrowed");
+    }
+
+    #[test]
+    fn test_cloned_unsized() {
+        assert_eq!(take_str(ClonedUnsized("str")), "str");
+
+        let v = vec![String::from("a")];
+        let taken: Vec<String> = Take::<[String]>::take_owned(ClonedUnsized(&v[..]));
+        assert_eq!(taken, v);
+    }
+}
@@ -0,0 +1,96 @@
+use alloc::{
+    borrow::{Cow, ToOwned},
+    rc::Rc,
+    sync::Arc,
+};
+
+use super::*;
+
+/// Taking ownership by moving if possible, and cloning otherwise.
+///
+/// This generalizes how `DerefTake` is implemented for `Rc<T>`: the value is moved out if the
+/// container is the only owner, and cloned if it isn't.
+pub trait CloneTake<T: ?Sized + IntoOwned> : Sized {
+    /// Takes ownership of the owned version of `T`, cloning if necessary.
+    fn clone_take(self) -> T::Owned;
+}
+
+impl<T> CloneTake<T> for Rc<T>
+    where T: ?Sized + IntoOwned + ToOwned<Owned = <T as IntoOwned>::Owned>
+{
+    fn clone_take(self) -> <T as IntoOwned>::Owned {
+        self.try_deref_take().unwrap_or_else(|this| (*this).to_owned())
+    }
+}
+
+impl<T> CloneTake<T> for Arc<T>
+    where T: ?Sized + IntoOwned + ToOwned<Owned = <T as IntoOwned>::Owned>
+{
+    fn clone_take(self) -> <T as IntoOwned>::Owned {
+        self.try_deref_take().unwrap_or_else(|this| (*this).to_owned())
+    }
+}
+
+impl<'a, T> CloneTake<T> for Cow<'a, T>
+    where T: ?Sized + IntoOwned + ToOwned<Owned = <T as IntoOwned>::Owned>
+{
+    fn clone_take(self) -> <T as IntoOwned>::Owned {
+        self.into_owned()
+    }
+}
+
+impl<'a, T> CloneTake<T> for TakeCow<'a, T>
+    where T: ?Sized + IntoOwned + ToOwned<Owned = <T as IntoOwned>::Owned>
+{
+    fn clone_take(self) -> <T as IntoOwned>::Owned {
+        self.take()
+    }
+}
+
+impl<T> CloneTake<T> for &T
+    where T: ?Sized + IntoOwned + ToOwned<Owned = <T as IntoOwned>::Owned>
+{
+    fn clone_take(self) -> <T as IntoOwned>::Owned {
+        self.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use dropcheck::DropCheck;
+
+    fn clone_take_str(src: impl CloneTake<str>) -> String {
+        src.clone_take()
+    }
+
+    #[test]
+    fn test_clone_take() {
+        let check = DropCheck::new();
+
+        let (t1, s1) = check.pair();
+        let rc = Rc::new(t1);
+        let t1 = rc.clone_take();
+        assert!(s1.is_not_dropped());
+        drop(t1);
+        assert!(s1.is_dropped());
+
+        let (t1, s1) = check.pair();
+        let arc = Arc::new(t1);
+        let arc2 = Arc::clone(&arc);
+        let t1_clone = arc.clone_take();
+        drop(arc2);
+        assert!(s1.is_dropped());
+        drop(t1_clone);
+
+        let rc: Rc<str> = Rc::from("rc");
+        let rc2 = Rc::clone(&rc);
+        assert_eq!(clone_take_str(rc), "rc");
+        assert_eq!(clone_take_str(rc2), "rc");
+
+        assert_eq!(clone_take_str(Cow::Borrowed("cow")), "cow");
+        assert_eq!(clone_take_str(TakeCow::Owned(String::from("takecow"))), "takecow");
+        assert_eq!(clone_take_str("ref"), "ref");
+    }
+}
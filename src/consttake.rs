@@ -0,0 +1,46 @@
+//! Const versions of the impls for sized types.
+//!
+//! Trait methods can't be called in const contexts until const traits are stable, so these are
+//! free functions mirroring what `IntoOwned` and `Take` do for sized types.
+//!
+//! ```
+//! use core::mem::ManuallyDrop;
+//!
+//! const VALUE: u32 = owned::consttake::take_sized(ManuallyDrop::new(42));
+//! assert_eq!(VALUE, 42);
+//! ```
+
+use core::mem::ManuallyDrop;
+
+/// Same as `IntoOwned::into_owned_unchecked()` for sized types.
+///
+/// # Safety
+///
+/// Same as `IntoOwned::into_owned_unchecked()`.
+pub const unsafe fn into_owned_unchecked<T>(this: &mut ManuallyDrop<T>) -> T {
+    (this as *const ManuallyDrop<T> as *const T).read()
+}
+
+/// Same as `Take::take_sized()` for a `ManuallyDrop<T>`.
+pub const fn take_sized<T>(src: ManuallyDrop<T>) -> T {
+    let mut src = src;
+    unsafe { into_owned_unchecked(&mut src) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TAKEN: [u8; 3] = take_sized(ManuallyDrop::new([1, 2, 3]));
+
+    const UNCHECKED: Option<&str> = {
+        let mut src = ManuallyDrop::new(Some("const"));
+        unsafe { into_owned_unchecked(&mut src) }
+    };
+
+    #[test]
+    fn test_const() {
+        assert_eq!(TAKEN, [1, 2, 3]);
+        assert_eq!(UNCHECKED, Some("const"));
+    }
+}
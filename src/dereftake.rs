@@ -1,11 +1,29 @@
-use std::mem::{self, ManuallyDrop};
-use std::ops;
-use std::slice;
-use std::rc::Rc;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops;
+use core::ptr;
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    borrow::{Cow, ToOwned},
+    ffi::CString,
+    rc::Rc,
+    string::String,
+    sync::Arc,
+};
+
+#[cfg(feature = "std")]
+use std::ffi::OsString;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
 
 use super::*;
 
 /// `Deref`, but for taking ownership.
+///
+/// # Safety
+///
+/// This trait is unsafe to implement because `deref_take_unsized()` must not `drop()` or otherwise
+/// use the taken value after the closure returns.
 pub unsafe trait DerefTake : ops::Deref {
     /// Takes ownership, consuming the container.
     fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
@@ -13,54 +31,161 @@ pub unsafe trait DerefTake : ops::Deref {
 
     /// Takes ownership of an unsized type with the aid of a closure.
     ///
-    /// The closure is called with an mutable reference to `ManuallyDrop<T>`. After the closure
+    /// The closure is called with a `TakeSlot<T>` to move the value out of. After the closure
     /// returns the memory occupied by the value will be deallocated, but `drop()` will *not* be
-    /// called on the value itself.
+    /// called on the value itself. This also applies if the closure panics: the memory is still
+    /// deallocated, and whatever the closure hadn't taken yet is leaked.
     fn deref_take_unsized<F, R>(self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<Self::Target>) -> R;
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R;
+
+    /// Takes ownership, in the owned form of your choice.
+    fn deref_take_as<O>(self) -> O
+        where Self: Sized,
+              Self::Target: IntoOwnedAs<O>
+    {
+        self.deref_take_unsized(|src| unsafe { Self::Target::into_owned_as_unchecked(TakeSlot::into_raw(src)) })
+    }
+
+    /// Takes ownership, moving the value directly into `dest`.
+    ///
+    /// Unlike `deref_take()` the value isn't returned on the stack, which avoids an extra copy for
+    /// large values. Any previous value in `dest` is overwritten without being dropped.
+    fn deref_take_into(self, dest: &mut MaybeUninit<Self::Target>) -> &mut Self::Target
+        where Self: Sized,
+              Self::Target: Sized
+    {
+        self.deref_take_unsized(|src| unsafe {
+            ptr::copy_nonoverlapping(TakeSlot::as_ptr(&src), dest.as_mut_ptr(), 1);
+            &mut *dest.as_mut_ptr()
+        })
+    }
+
+    /// Takes ownership through two layers of pointers, such as `Box<Rc<T>>` or `Rc<Box<[T]>>`.
+    ///
+    /// The inner pointer is moved out of the outer one, and then taken from. Deeper nestings can
+    /// be peeled by calling `flatten_take_unsized()` within the closure.
+    fn flatten_take<T>(self) -> T::Owned
+        where Self: Sized,
+              Self::Target: Sized + Take<T>,
+              T: ?Sized + IntoOwned
+    {
+        self.flatten_take_unsized(|src| src.into_owned())
+    }
+
+    /// Takes ownership through two layers of pointers with the aid of a closure.
+    ///
+    /// Like `flatten_take()`, with the closure called as per `Take::take_unsized()`.
+    fn flatten_take_unsized<T, F, R>(self, f: F) -> R
+        where Self: Sized,
+              Self::Target: Sized + Take<T>,
+              T: ?Sized,
+              F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(|src| src.read().take_unsized(f))
+    }
+
+    /// Takes ownership of a prefix of a slice with the aid of a closure.
+    ///
+    /// Like `deref_take_unsized()`, except the closure is given the slot by reference, along with a
+    /// count of consumed elements. The closure is expected to move elements out from the front of
+    /// the slice, incrementing the count as it goes. After the closure returns, or if it panics,
+    /// the remaining elements are dropped and the memory is deallocated.
+    ///
+    /// Counts past the end of the slice are treated as the length of the slice.
+    fn deref_take_unsized_with_len<T, F, R>(self, f: F) -> R
+        where Self: Sized + ops::Deref<Target = [T]>,
+              F: FnOnce(&mut TakeSlot<'_, [T]>, &mut usize) -> R
+    {
+        struct Guard<T> {
+            src: *mut [T],
+            consumed: usize,
+        }
+
+        impl<T> Drop for Guard<T> {
+            fn drop(&mut self) {
+                unsafe {
+                    #[cfg(feature = "debug-leakcheck")]
+                    crate::leakcheck::assert_untaken(&*self.src);
+
+                    let len = self.src.len();
+                    let consumed = self.consumed.min(len);
+                    let rest = (self.src as *mut T).add(consumed);
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(rest, len - consumed));
+                }
+            }
+        }
+
+        self.deref_take_unsized(|mut src| {
+            let mut guard = Guard {
+                src: TakeSlot::as_mut_ptr(&mut src),
+                consumed: 0,
+            };
+            f(&mut src, &mut guard.consumed)
+        })
+    }
 }
 
-unsafe impl<T: ?Sized> DerefTake for Box<T> {
+#[cfg(feature = "alloc")]
+unsafe impl DerefTake for String {
     fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
         where Self::Target: IntoOwned
     {
-        self.deref_take_unsized(|src| {
-            unsafe { Self::Target::into_owned_unchecked(src) }
-        })
+        self
     }
 
     fn deref_take_unsized<F, R>(self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<Self::Target>) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
     {
-        let ptr = Box::into_raw(self) as *mut ManuallyDrop<T>;
+        self.into_bytes().deref_take_unsized(|src| {
+            // str has the same layout as [u8]
+            let src = TakeSlot::into_raw(src) as *mut ManuallyDrop<[u8]> as *mut ManuallyDrop<str>;
+            unsafe { TakeSlot::scoped(&mut *src, f) }
+        })
+    }
+}
 
-        unsafe {
-            let mut this: Box<ManuallyDrop<T>> = Box::from_raw(ptr);
-            f(&mut this)
-        }
+#[cfg(feature = "alloc")]
+unsafe impl DerefTake for CString {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        self.into_boxed_c_str().deref_take_unsized(f)
     }
 }
 
-unsafe impl<T> DerefTake for Vec<T> {
+#[cfg(feature = "std")]
+unsafe impl DerefTake for OsString {
     fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
         where Self::Target: IntoOwned
     {
-        self.deref_take_unsized(|src| {
-            unsafe { Self::Target::into_owned_unchecked(src) }
-        })
+        self
     }
 
-    fn deref_take_unsized<F, R>(mut self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<Self::Target>) -> R
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        self.into_boxed_os_str().deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "std")]
+unsafe impl DerefTake for PathBuf {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
     {
-        unsafe {
-            let len = self.len();
+        self
+    }
 
-            // Setting the len to 0 means a panic won't call drop on any of the contained values.
-            self.set_len(0);
-            let src: &mut [T] = slice::from_raw_parts_mut(self.as_mut_ptr(), len);
-            f(mem::transmute(src))
-        }
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        self.into_boxed_path().deref_take_unsized(f)
     }
 }
 
@@ -68,29 +193,71 @@ unsafe impl<T> DerefTake for ManuallyDrop<T> {
     fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
         where Self::Target: IntoOwned
     {
-        self.deref_take_unsized(|src| {
-            unsafe { Self::Target::into_owned_unchecked(src) }
-        })
+        self.deref_take_unsized(|src| src.into_owned())
     }
 
     fn deref_take_unsized<F, R>(mut self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<Self::Target>) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
     {
-        f(&mut self)
+        unsafe { TakeSlot::scoped(&mut self, f) }
     }
 }
 
+/// Implements `DerefTake` for `Pin<P>` in terms of `P`'s impl.
+///
+/// Pinned `RawDerefTake` pointers such as `Pin<Box<T>>` are covered by the blanket impl instead,
+/// which rules out a single impl for every `Pin<P>`: downstream crates could implement
+/// `RawDerefTake` for their own `Pin<P>`. So every other `DerefTake` impl in this crate invokes
+/// this next to it, while `DerefTake` impls in other crates only get `Pin` support via
+/// `RawDerefTake`. Unpinning is sound as the target is `Unpin`.
+macro_rules! impl_deref_take_for_pin {
+    ($(#[$attr:meta])* [$($g:tt)*] $ty:ty) => {
+        $(#[$attr])*
+        unsafe impl<$($g)*> $crate::DerefTake for ::core::pin::Pin<$ty>
+            where <$ty as ::core::ops::Deref>::Target: Unpin
+        {
+            fn deref_take(self) -> <Self::Target as $crate::IntoOwned>::Owned
+                where Self::Target: $crate::IntoOwned
+            {
+                $crate::DerefTake::deref_take(::core::pin::Pin::into_inner(self))
+            }
+
+            fn deref_take_unsized<F, R>(self, f: F) -> R
+                where F: FnOnce($crate::TakeSlot<'_, Self::Target>) -> R
+            {
+                $crate::DerefTake::deref_take_unsized(::core::pin::Pin::into_inner(self), f)
+            }
+        }
+    };
+}
+// Used by the impls for types from other crates.
+#[allow(unused_imports)]
+pub(crate) use impl_deref_take_for_pin;
+
+impl_deref_take_for_pin!([T] ManuallyDrop<T>);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] [T: Clone] Rc<T>);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] [T: Clone] Arc<T>);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] [T: Clone] Rc<[T]>);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] [T: Clone] Arc<[T]>);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] [] Rc<str>);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] [] Arc<str>);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] [] String);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] [] CString);
+impl_deref_take_for_pin!(#[cfg(feature = "std")] [] OsString);
+impl_deref_take_for_pin!(#[cfg(feature = "std")] [] PathBuf);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] ['a, B: ?Sized + IntoOwned + ToOwned<Owned = <B as IntoOwned>::Owned>] Cow<'a, B>);
+impl_deref_take_for_pin!(#[cfg(feature = "alloc")] ['a, T: ?Sized + IntoOwned + ToOwned<Owned = <T as IntoOwned>::Owned>] TakeCow<'a, T>);
+
+#[cfg(feature = "alloc")]
 unsafe impl<T: Clone> DerefTake for Rc<T> {
     fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
         where Self::Target: IntoOwned
     {
-        self.deref_take_unsized(|src| {
-            unsafe { Self::Target::into_owned_unchecked(src) }
-        })
+        self.deref_take_unsized(|src| src.into_owned())
     }
 
     fn deref_take_unsized<F, R>(self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<Self::Target>) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
     {
         // Convert the Rc so that drop won't be called on the contents
         let mut this: Rc<ManuallyDrop<T>> = unsafe { Rc::from_raw(Rc::into_raw(self) as *const _) };
@@ -99,14 +266,128 @@ unsafe impl<T: Clone> DerefTake for Rc<T> {
         //
         // ManuallyDrop<T> is a #[repr(C)] wrapper, so it doesn't matter that we're doing the clone
         // here rather than above.
-        f(Rc::make_mut(&mut this))
+        unsafe { TakeSlot::scoped(Rc::make_mut(&mut this), f) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Clone> DerefTake for Arc<T> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        // Same strategy as the Rc impl.
+        let mut this: Arc<ManuallyDrop<T>> = unsafe { Arc::from_raw(Arc::into_raw(self) as *const _) };
+        unsafe { TakeSlot::scoped(Arc::make_mut(&mut this), f) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Clone> DerefTake for Rc<[T]> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        // Move the elements if we're the only owner, and clone them otherwise.
+        let mut f = Some(f);
+        match self.try_deref_take_unsized(|src| f.take().unwrap()(src)) {
+            Ok(r) => r,
+            Err(shared) => shared.to_vec().take_unsized(f.take().unwrap()),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Clone> DerefTake for Arc<[T]> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        let mut f = Some(f);
+        match self.try_deref_take_unsized(|src| f.take().unwrap()(src)) {
+            Ok(r) => r,
+            Err(shared) => shared.to_vec().take_unsized(f.take().unwrap()),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl DerefTake for Rc<str> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        let mut f = Some(f);
+        match self.try_deref_take_unsized(|src| f.take().unwrap()(src)) {
+            Ok(r) => r,
+            Err(shared) => String::from(&*shared).take_unsized(f.take().unwrap()),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl DerefTake for Arc<str> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        let mut f = Some(f);
+        match self.try_deref_take_unsized(|src| f.take().unwrap()(src)) {
+            Ok(r) => r,
+            Err(shared) => String::from(&*shared).take_unsized(f.take().unwrap()),
+        }
+    }
+}
+
+/// Moves out of `Cow::Owned`, and only clones `Cow::Borrowed`.
+#[cfg(feature = "alloc")]
+unsafe impl<'a, B> DerefTake for Cow<'a, B>
+    where B: ?Sized + IntoOwned + ToOwned<Owned = <B as IntoOwned>::Owned>
+{
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.into_owned()
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        self.into_owned().take_unsized(f)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod test {
     use super::*;
 
+    use core::pin::Pin;
+
     use dropcheck::{DropCheck, DropToken};
 
     #[test]
@@ -119,6 +400,69 @@ mod test {
         assert!(state.is_not_dropped());
     }
 
+    #[test]
+    fn test_deref_take_into() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let mut dest = MaybeUninit::uninit();
+        let token = Box::new(token).deref_take_into(&mut dest);
+        assert!(state.is_not_dropped());
+        unsafe { ptr::drop_in_place(token) };
+        assert!(state.is_dropped());
+    }
+
+    #[test]
+    fn test_pin() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let pinned = Box::pin(token);
+        let _token = pinned.deref_take();
+        assert!(state.is_not_dropped());
+
+        let pinned = Pin::new(vec![1u8, 2, 3]);
+        assert_eq!(pinned.deref_take_unsized(|src| src.len()), 3);
+
+        let pinned = Pin::new(Owned::<str>::new(String::from("owned")));
+        assert_eq!(pinned.deref_take(), "owned");
+    }
+
+    #[test]
+    fn test_flatten_take() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let nested = Box::new(Rc::new(token));
+        let _token = nested.flatten_take::<DropToken>();
+        assert!(state.is_not_dropped());
+
+        let nested: Rc<Box<[u8]>> = Rc::new(Box::new([1, 2, 3]));
+        let nested2 = Rc::clone(&nested);
+        assert_eq!(nested.flatten_take::<[u8]>(), [1, 2, 3]);
+        assert_eq!(Take::<[u8]>::take_owned(nested2.deref_take()), [1, 2, 3]);
+
+        let deep = Box::new(Box::new(Rc::new(String::from("deep"))));
+        let s = deep.flatten_take_unsized(|src: TakeSlot<'_, Rc<String>>| src.read().flatten_take::<String>());
+        assert_eq!(s, "deep");
+    }
+
+    #[test]
+    fn test_strings() {
+        fn take_str<P: DerefTake<Target = str>>(src: P) -> String {
+            src.deref_take()
+        }
+
+        let s = String::from("string");
+        let ptr = s.as_ptr();
+        let s = take_str(s);
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(take_str(Box::<str>::from("boxed")), "boxed");
+        assert_eq!(String::from("len").deref_take_unsized(|src| src.len()), 3);
+
+        assert_eq!(CString::new("c").unwrap().deref_take_unsized(|src| src.to_bytes().len()), 1);
+    }
+
     #[test]
     fn test_vec() {
         let check = DropCheck::new();
@@ -155,4 +499,212 @@ mod test {
         drop(rc2);
         assert!(s1.is_dropped());
     }
+
+    #[test]
+    fn test_arc() {
+        let check = DropCheck::new();
+
+        let (t1, s1) = check.pair();
+        let arc1 = Arc::new(t1);
+
+        // only one owner, so no need to drop
+        let _t1 = arc1.deref_take();
+        assert!(s1.is_not_dropped());
+
+        let (t1, s1) = check.pair();
+        let arc1 = Arc::new(t1);
+        let arc2 = Arc::clone(&arc1);
+
+        // two owners, so deref_take() had to clone
+        let _t1_clone = arc1.deref_take();
+        assert!(s1.is_not_dropped());
+
+        drop(arc2);
+        assert!(s1.is_dropped());
+
+        // moving across threads
+        let (t1, s1) = check.pair();
+        let arc1 = Arc::new(t1);
+        let t1 = std::thread::spawn(move || arc1.deref_take()).join().unwrap();
+        assert!(s1.is_not_dropped());
+        drop(t1);
+        assert!(s1.is_dropped());
+    }
+
+    #[test]
+    fn test_panic_deallocates() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        // Contents are leaked, not dropped, if the closure panics.
+        let rc = Rc::new(());
+
+        let v = vec![Rc::clone(&rc); 10];
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            v.deref_take_unsized(|_| panic!())
+        }));
+        assert!(r.is_err());
+        assert_eq!(Rc::strong_count(&rc), 11);
+
+        let boxed = vec![Rc::clone(&rc); 10].into_boxed_slice();
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            boxed.deref_take_unsized(|_| panic!())
+        }));
+        assert!(r.is_err());
+        assert_eq!(Rc::strong_count(&rc), 21);
+
+        // Release the leaked clones, so Miri's leak check passes.
+        for _ in 0 .. 20 {
+            unsafe { Rc::decrement_strong_count(Rc::as_ptr(&rc)) }
+        }
+    }
+
+    #[test]
+    fn test_with_len() {
+        let check = DropCheck::new();
+
+        let tokens: Vec<_> = (0 .. 10).map(|_| check.pair()).collect();
+        let (tokens, states): (Vec<_>, Vec<_>) = tokens.into_iter().unzip();
+
+        let taken: Vec<DropToken> = tokens.deref_take_unsized_with_len(|src, consumed| {
+            let mut taken = vec![];
+            while *consumed < 3 {
+                taken.push(unsafe { ptr::read(&src[*consumed]) });
+                *consumed += 1;
+            }
+            taken
+        });
+
+        assert!(states[0 .. 3].iter().all(|s| s.is_not_dropped()));
+        assert!(states[3 ..].iter().all(|s| s.is_dropped()));
+        drop(taken);
+        assert!(check.all_dropped());
+
+        // on panic the rest is still dropped
+        let check = DropCheck::new();
+        let tokens: Vec<_> = (0 .. 10).map(|_| check.pair()).collect();
+        let (tokens, states): (Vec<_>, Vec<_>) = tokens.into_iter().unzip();
+        let boxed = tokens.into_boxed_slice();
+
+        let mut taken = vec![];
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            boxed.deref_take_unsized_with_len(|src, consumed| {
+                for i in 0 .. 5 {
+                    taken.push(unsafe { ptr::read(&src[i]) });
+                    *consumed += 1;
+                }
+                panic!()
+            })
+        }));
+        assert!(r.is_err());
+        assert!(states[0 .. 5].iter().all(|s| s.is_not_dropped()));
+        assert!(states[5 ..].iter().all(|s| s.is_dropped()));
+        drop(taken);
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_rc_slice() {
+        let check = DropCheck::new();
+
+        let rc: Rc<[DropToken]> = Rc::from(vec![check.token(); 3]);
+        let v = rc.deref_take();
+        assert!(check.none_dropped());
+        drop(v);
+        assert!(check.all_dropped());
+
+        let check = DropCheck::new();
+        let (t1, s1) = check.pair();
+        let arc: Arc<[DropToken]> = Arc::from(vec![t1]);
+        let arc2 = Arc::clone(&arc);
+        let v = arc.deref_take();
+        drop(arc2);
+        assert!(s1.is_dropped());
+        assert_eq!(v.len(), 1);
+
+        let rc: Rc<str> = Rc::from("hello");
+        let rc2 = Rc::clone(&rc);
+        assert_eq!(rc.deref_take(), "hello");
+        assert_eq!(rc2.deref_take(), "hello");
+
+        let arc: Arc<str> = Arc::from("hello");
+        assert_eq!(Take::<str>::take_owned(arc), "hello");
+    }
+
+    #[test]
+    fn test_cow() {
+        let check = DropCheck::new();
+
+        #[derive(Clone)]
+        struct Token(#[allow(dead_code)] DropToken);
+
+        let (t1, s1) = check.pair();
+        let cow: Cow<Token> = Cow::Owned(Token(t1));
+        let t1 = cow.deref_take();
+        assert!(s1.is_not_dropped());
+        drop(t1);
+        assert!(s1.is_dropped());
+
+        let (t1, s1) = check.pair();
+        let t1 = Token(t1);
+        let cow: Cow<Token> = Cow::Borrowed(&t1);
+        let t1_clone = cow.deref_take();
+        assert!(s1.is_not_dropped());
+        drop(t1_clone);
+        assert!(s1.is_not_dropped());
+        drop(t1);
+        assert!(s1.is_dropped());
+
+        let s = String::from("owned");
+        let ptr = s.as_ptr();
+        let cow: Cow<str> = Cow::Owned(s);
+        let s = cow.deref_take();
+        assert_eq!(s.as_ptr(), ptr);
+
+        let cow: Cow<[u8]> = Cow::Borrowed(b"borrowed");
+        assert_eq!(cow.deref_take_unsized(|src| src.len()), 8);
+    }
+
+    #[test]
+    #[cfg(feature = "allocator_api")]
+    fn test_allocator_api() {
+        use std::alloc::{AllocError, Allocator, Global, Layout};
+        use std::cell::Cell;
+        use std::ptr::NonNull;
+
+        struct Counting<'a>(&'a Cell<isize>);
+
+        unsafe impl Allocator for Counting<'_> {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.set(self.0.get() + 1);
+                Global.allocate(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                self.0.set(self.0.get() - 1);
+                Global.deallocate(ptr, layout)
+            }
+        }
+
+        let check = DropCheck::new();
+        let live = Cell::new(0);
+
+        let (token, state) = check.pair();
+        let boxed = Box::new_in(token, Counting(&live));
+        assert_eq!(live.get(), 1);
+        let token = boxed.deref_take();
+        assert_eq!(live.get(), 0);
+        assert!(state.is_not_dropped());
+        drop(token);
+        assert!(state.is_dropped());
+
+        let check = DropCheck::new();
+        let mut v = Vec::new_in(Counting(&live));
+        v.push(check.token());
+        v.push(check.token());
+        let v2: Vec<DropToken> = v.deref_take();
+        assert_eq!(live.get(), 0);
+        assert!(check.none_dropped());
+        drop(v2);
+        assert!(check.all_dropped());
+    }
 }
@@ -0,0 +1,225 @@
+use core::ptr;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::*;
+
+/// Combinators for taking from `DerefTake` containers.
+pub trait DerefTakeExt : DerefTake + Sized {
+    /// Takes ownership and transforms the owned value.
+    fn map_take<U, F>(self, f: F) -> U
+        where Self::Target: IntoOwned,
+              F: FnOnce(<Self::Target as IntoOwned>::Owned) -> U
+    {
+        f(self.deref_take())
+    }
+
+    /// Takes ownership, transforms the owned value into another container, and takes from that.
+    fn and_then_take<P, F>(self, f: F) -> <P::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned,
+              P: DerefTake,
+              P::Target: IntoOwned,
+              F: FnOnce(<Self::Target as IntoOwned>::Owned) -> P
+    {
+        f(self.deref_take()).deref_take()
+    }
+
+    /// Takes ownership after calling `f` on a reference to the value.
+    fn inspect_take<F>(self, f: F) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned,
+              F: FnOnce(&Self::Target)
+    {
+        f(&self);
+        self.deref_take()
+    }
+
+    /// Takes ownership of the values of two containers.
+    ///
+    /// If taking from `other` panics, the value already taken from `self` is dropped.
+    fn zip_take<P>(self, other: P) -> (<Self::Target as IntoOwned>::Owned, <P::Target as IntoOwned>::Owned)
+        where Self::Target: IntoOwned,
+              P: DerefTake,
+              P::Target: IntoOwned
+    {
+        let this = self.deref_take();
+        (this, other.deref_take())
+    }
+
+    /// Takes ownership of the values of two containers with the aid of a closure.
+    ///
+    /// The closure is called with both slots, as per `DerefTake::deref_take_unsized()`. If it
+    /// panics, the memory of both containers is still deallocated.
+    fn zip_take_unsized<P, F, R>(self, other: P, f: F) -> R
+        where P: DerefTake,
+              F: FnOnce(TakeSlot<'_, Self::Target>, TakeSlot<'_, P::Target>) -> R
+    {
+        self.deref_take_unsized(|this| other.deref_take_unsized(|other| f(this, other)))
+    }
+
+    /// Moves each element of a slice container out, folding them into an accumulator.
+    ///
+    /// If `f` panics the elements not yet visited are dropped, and the memory is deallocated.
+    fn take_fold<T, B, F>(self, init: B, f: F) -> B
+        where Self: DerefTake<Target = [T]>,
+              F: FnMut(B, T) -> B
+    {
+        self.deref_take_unsized(|slot| fold_slot(slot, init, f))
+    }
+
+    /// Moves each element of a slice container out, reducing them with `f`.
+    ///
+    /// Returns `None` if the slice is empty. Panics are handled as per `take_fold()`.
+    fn take_reduce<T, F>(self, mut f: F) -> Option<T>
+        where Self: DerefTake<Target = [T]>,
+              F: FnMut(T, T) -> T
+    {
+        self.take_fold(None, |acc, item| match acc {
+            None => Some(item),
+            Some(acc) => Some(f(acc, item)),
+        })
+    }
+
+    /// Moves the elements of a slice container that match `pred` into a `Vec`, dropping the rest.
+    ///
+    /// If `pred` panics the elements not yet visited are dropped along with those already moved,
+    /// and the memory is deallocated.
+    #[cfg(feature = "alloc")]
+    fn take_filter<T, P>(self, mut pred: P) -> Vec<T>
+        where Self: DerefTake<Target = [T]>,
+              P: FnMut(&T) -> bool
+    {
+        self.take_fold(Vec::new(), |mut taken, item| {
+            if pred(&item) {
+                taken.push(item);
+            }
+            taken
+        })
+    }
+}
+
+impl<C: DerefTake> DerefTakeExt for C {}
+
+fn fold_slot<T, B, F>(slot: TakeSlot<'_, [T]>, init: B, mut f: F) -> B
+    where F: FnMut(B, T) -> B
+{
+    // Drops the elements that haven't been moved out yet if f panics.
+    struct Guard<T> {
+        ptr: *mut T,
+        len: usize,
+        next: usize,
+    }
+
+    impl<T> Drop for Guard<T> {
+        fn drop(&mut self) {
+            unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.add(self.next), self.len - self.next));
+            }
+        }
+    }
+
+    let slice = TakeSlot::into_raw(slot);
+    let mut guard = Guard { ptr: slice.as_mut_ptr(), len: slice.len(), next: 0 };
+
+    let mut acc = init;
+    while guard.next < guard.len {
+        let item = unsafe { guard.ptr.add(guard.next).read() };
+        guard.next += 1;
+        acc = f(acc, item);
+    }
+    acc
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use alloc::{boxed::Box, rc::Rc, string::String, vec::Vec};
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_map_take() {
+        let boxed: Box<str> = "hello".into();
+        assert_eq!(boxed.map_take(|s| s.len()), 5);
+
+        let nested = Box::new(vec![1u8, 2, 3]);
+        let v: Vec<u8> = nested.and_then_take(|v: Vec<u8>| v);
+        assert_eq!(v, [1, 2, 3]);
+
+        let mut seen = 0;
+        let s: String = Rc::new(String::from("rc")).inspect_take(|s| seen = s.len());
+        assert_eq!((s.as_str(), seen), ("rc", 2));
+    }
+
+    #[test]
+    fn test_take_fold() {
+        let v: Vec<String> = (0 .. 4).map(|i| i.to_string()).collect();
+        assert_eq!(v.take_fold(String::new(), |acc, s| acc + &s), "0123");
+
+        let boxed: Box<[String]> = vec![String::from("a"), String::from("b")].into_boxed_slice();
+        assert_eq!(boxed.take_reduce(|a, b| a + &b).unwrap(), "ab");
+        assert_eq!(Vec::<u8>::new().take_reduce(|a, b| a + b), None);
+    }
+
+    #[test]
+    fn test_take_filter() {
+        let check = DropCheck::new();
+
+        let (tokens, states): (Vec<_>, Vec<_>) = (0 .. 6).map(|_| check.pair()).unzip();
+        let mut n = 0;
+        let kept = tokens.take_filter(|_| { n += 1; n % 2 == 1 });
+        assert_eq!(kept.len(), 3);
+        assert!(states.iter().step_by(2).all(|s| s.is_not_dropped()));
+        assert!(states.iter().skip(1).step_by(2).all(|s| s.is_dropped()));
+        drop(kept);
+        assert!(check.all_dropped());
+
+        let boxed: Box<[String]> = vec![String::from("a"), String::from("bb")].into_boxed_slice();
+        assert_eq!(boxed.take_filter(|s| s.len() > 1), ["bb"]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_take_fold_panic() {
+        let check = DropCheck::new();
+
+        let v: Vec<_> = (0 .. 10).map(|_| check.token()).collect();
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v.take_fold(0, |n, _token| if n == 5 { panic!() } else { n + 1 })
+        }));
+        assert!(r.is_err());
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_take_filter_panic() {
+        let check = DropCheck::new();
+
+        let v: Vec<_> = (0 .. 10).map(|_| check.token()).collect();
+        let mut n = 0;
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v.take_filter(|_| {
+                n += 1;
+                if n == 6 { panic!() } else { n % 2 == 1 }
+            })
+        }));
+        assert!(r.is_err());
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_zip_take() {
+        let check = DropCheck::new();
+
+        let (a, b) = Box::new(check.token()).zip_take(vec![check.token()]);
+        assert!(check.none_dropped());
+        drop((a, b));
+        assert!(check.all_dropped());
+
+        let boxed: Box<str> = "a".into();
+        let r = boxed.zip_take_unsized(vec![1u8, 2], |a, b| a.len() + b.len());
+        assert_eq!(r, 3);
+    }
+}
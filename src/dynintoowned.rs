@@ -0,0 +1,160 @@
+use core::mem::{self, ManuallyDrop};
+use core::ptr;
+
+use alloc::alloc::{alloc, handle_alloc_error, Layout};
+use alloc::boxed::Box;
+
+use super::*;
+
+/// Unsized types, such as trait objects, whose owned form is `Box<Self>`.
+///
+/// Implemented for every such type, so generic code can use `T: ?Sized + DynIntoOwned` as a bound
+/// and get a `Box<T>` out of any `Take<T>`. Use `impl_into_owned_for_dyn!` to implement
+/// `IntoOwned` for a trait object.
+pub trait DynIntoOwned : IntoOwned<Owned = Box<Self>> {
+}
+
+impl<T: ?Sized + IntoOwned<Owned = Box<T>>> DynIntoOwned for T {
+}
+
+/// Implements `IntoOwned`, and thus `DynIntoOwned`, for a trait object.
+///
+/// The owned form is `Box<dyn Trait>`, so taking from a `Box<dyn Trait>` is a no-op, and taking
+/// from other containers moves the value into a new box.
+///
+/// ```
+/// use owned::{impl_into_owned_for_dyn, Take};
+///
+/// trait Shape {
+///     fn area(&self) -> f64;
+/// }
+///
+/// impl_into_owned_for_dyn!(Shape);
+/// impl_into_owned_for_dyn!(Shape + Send);
+///
+/// struct Square(f64);
+///
+/// impl Shape for Square {
+///     fn area(&self) -> f64 { self.0 * self.0 }
+/// }
+///
+/// let boxed: Box<dyn Shape> = Box::new(Square(2.0));
+/// let owned: Box<dyn Shape> = Take::<dyn Shape>::take_owned(boxed);
+/// assert_eq!(owned.area(), 4.0);
+/// ```
+#[macro_export]
+macro_rules! impl_into_owned_for_dyn {
+    ($($bounds:tt)+) => {
+        unsafe impl $crate::IntoOwned for dyn $($bounds)+ {
+            type Owned = $crate::__private::Box<Self>;
+
+            unsafe fn into_owned_unchecked(this: &mut $crate::__private::ManuallyDrop<Self>) -> Self::Owned {
+                $crate::__private::box_from_manually_drop(this)
+            }
+
+            fn box_into_owned(this: $crate::__private::Box<Self>) -> Self::Owned {
+                this
+            }
+        }
+    };
+}
+
+/// Moves an unsized value into a new box.
+///
+/// # Safety
+///
+/// Same as `IntoOwned::into_owned_unchecked()`.
+#[doc(hidden)]
+pub unsafe fn box_from_manually_drop<T: ?Sized>(this: &mut ManuallyDrop<T>) -> Box<T> {
+    let layout = Layout::for_value::<T>(&**this);
+    let src: *mut T = &mut **this;
+
+    let dst = if layout.size() == 0 {
+        ptr::null_mut::<u8>().wrapping_add(layout.align())
+    } else {
+        let dst = alloc(layout);
+        if dst.is_null() {
+            handle_alloc_error(layout);
+        }
+        dst
+    };
+    ptr::copy_nonoverlapping(src as *const u8, dst, layout.size());
+
+    let dst_ptr = raw::with_addr_of(src, dst);
+    debug_assert_eq!(mem::size_of_val(&*dst_ptr), layout.size());
+
+    Box::from_raw(dst_ptr)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::rc::Rc;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    trait Named {
+        fn name(&self) -> &str;
+    }
+
+    impl_into_owned_for_dyn!(Named);
+
+    struct Person(String, #[allow(dead_code)] DropToken);
+
+    impl Named for Person {
+        fn name(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl Named for () {
+        fn name(&self) -> &str {
+            "unit"
+        }
+    }
+
+    #[test]
+    fn test_box() {
+        let check = DropCheck::new();
+
+        let boxed: Box<dyn Named> = Box::new(Person(String::from("alice"), check.token()));
+        let ptr = &*boxed as *const dyn Named as *const u8;
+        let owned = Take::<dyn Named>::take_owned(boxed);
+        // With allocator_api, Box<T, A> doesn't use box_into_owned() and reallocates.
+        #[cfg(not(feature = "allocator_api"))]
+        assert_eq!(&*owned as *const dyn Named as *const u8, ptr);
+        #[cfg(feature = "allocator_api")]
+        let _ = ptr;
+        assert_eq!(owned.name(), "alice");
+        assert!(check.none_dropped());
+        drop(owned);
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_rc() {
+        let check = DropCheck::new();
+
+        let rc: Rc<dyn Named> = Rc::new(Person(String::from("bob"), check.token()));
+        let owned: Box<dyn Named> = rc.try_deref_take().ok().unwrap();
+        assert_eq!(owned.name(), "bob");
+        assert!(check.none_dropped());
+        drop(owned);
+        assert!(check.all_dropped());
+
+        let rc: Rc<dyn Named> = Rc::new(());
+        let owned = rc.try_deref_take().ok().unwrap();
+        assert_eq!(owned.name(), "unit");
+    }
+
+    fn take_dyn<T: ?Sized + DynIntoOwned>(b: Box<T>) -> Box<T> {
+        Take::<T>::take_owned(b)
+    }
+
+    #[test]
+    fn test_dyn_into_owned() {
+        let boxed: Box<dyn Named> = Box::new(());
+        assert_eq!(take_dyn(boxed).name(), "unit");
+    }
+}
@@ -0,0 +1,73 @@
+use alloc::boxed::Box;
+
+use super::*;
+
+/// An object-safe version of `Take`, for trait objects such as `Box<dyn DynTake<T>>`.
+///
+/// Implemented for every `Take<T>` type. `Box<dyn DynTake<T>>` in turn implements `Take<T>`.
+pub trait DynTake<T: ?Sized + IntoOwned> {
+    /// Takes ownership of the owned version of `T`.
+    fn dyn_take(self: Box<Self>) -> T::Owned;
+}
+
+impl<T: ?Sized + IntoOwned, C: Take<T>> DynTake<T> for C {
+    fn dyn_take(self: Box<Self>) -> T::Owned {
+        (*self).take_owned()
+    }
+}
+
+macro_rules! impl_take_for_dyn {
+    ($($bounds:tt)*) => {
+        unsafe impl<'a, T: ?Sized + IntoOwned> Take<T> for Box<dyn DynTake<T> $($bounds)* + 'a> {
+            fn take_owned(self) -> T::Owned {
+                self.dyn_take()
+            }
+
+            fn take_unsized<F, R>(self, f: F) -> R
+                where F: FnOnce(TakeSlot<'_, T>) -> R
+            {
+                self.dyn_take().take_unsized(f)
+            }
+        }
+
+        crate::takeorclone::impl_take_or_clone!(['a, T: ?Sized] Box<dyn DynTake<T> $($bounds)* + 'a> => T);
+        crate::fallible::impl_try_take!(['a, T: ?Sized] Box<dyn DynTake<T> $($bounds)* + 'a> => T);
+    };
+}
+
+impl_take_for_dyn!();
+impl_take_for_dyn!(+ Send);
+impl_take_for_dyn!(+ Send + Sync);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use alloc::{collections::VecDeque, rc::Rc, string::String, vec::Vec};
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_queue() {
+        let mut queue: VecDeque<Box<dyn DynTake<String>>> = VecDeque::new();
+        queue.push_back(Box::new(String::from("plain")));
+        queue.push_back(Box::new(Some(String::from("option"))));
+        queue.push_back(Box::new(RefCell::new(String::from("refcell"))));
+
+        let taken: Vec<String> = queue.into_iter().map(|taker| taker.dyn_take()).collect();
+        assert_eq!(taken, ["plain", "option", "refcell"]);
+    }
+
+    #[test]
+    fn test_unsized() {
+        let taker: Box<dyn DynTake<[u8]> + Send> = Box::new(vec![1u8, 2, 3]);
+        let len = Take::<[u8]>::take_unsized(taker, |slot| slot.len());
+        assert_eq!(len, 3);
+
+        let rc = Rc::new(());
+        let taker: Box<dyn DynTake<Rc<()>>> = Box::new(Box::new(Rc::clone(&rc)));
+        let taken = Take::<Rc<()>>::take_sized(taker);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(taken);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
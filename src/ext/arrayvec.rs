@@ -0,0 +1,87 @@
+use core::mem::{self, ManuallyDrop};
+use core::slice;
+
+use arrayvec::ArrayVec;
+
+use crate::*;
+
+unsafe impl<T, const CAP: usize> DerefTake for ArrayVec<T, CAP> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(mut self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        unsafe {
+            let len = self.len();
+
+            // As with Vec, a len of 0 means a panic won't call drop on any of the elements.
+            self.set_len(0);
+            let src: &mut [T] = slice::from_raw_parts_mut(self.as_mut_ptr(), len);
+            TakeSlot::scoped(mem::transmute::<&mut [T], &mut ManuallyDrop<[T]>>(src), f)
+        }
+    }
+}
+
+crate::dereftake::impl_deref_take_for_pin!([T, const CAP: usize] ArrayVec<T, CAP>);
+
+unsafe impl<T, const CAP: usize> Take<[T]> for ArrayVec<T, CAP> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+/// Fails if the length isn't `N`, returning the `ArrayVec`.
+unsafe impl<T, const CAP: usize, const N: usize> TryTake<[T; N]> for ArrayVec<T, CAP> {
+    type Error = Self;
+
+    fn try_take_unsized<F,R>(mut self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, [T; N]>) -> R
+    {
+        if self.len() != N {
+            return Err(self);
+        }
+
+        unsafe {
+            self.set_len(0);
+            let mut array = ManuallyDrop::new((self.as_ptr() as *const [T; N]).read());
+            Ok(TakeSlot::scoped(&mut array, f))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+crate::takeorclone::impl_take_or_clone!([T, const CAP: usize] ArrayVec<T, CAP> => [T]);
+crate::fallible::impl_try_take!([T, const CAP: usize] ArrayVec<T, CAP> => [T]);
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    #[test]
+    fn test_arrayvec() {
+        let check = DropCheck::new();
+
+        let v: ArrayVec<DropToken, 8> = (0 .. 3).map(|_| check.token()).collect();
+        let taken: Vec<DropToken> = v.deref_take();
+        assert_eq!(taken.len(), 3);
+        assert!(check.none_dropped());
+        drop(taken);
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_array() {
+        let v: ArrayVec<String, 4> = ["a", "b"].iter().map(|s| String::from(*s)).collect();
+        let v = TryTake::<[String; 3]>::try_take_sized(v).unwrap_err();
+        let array: [String; 2] = v.try_take_sized().unwrap();
+        assert_eq!(array, ["a", "b"]);
+    }
+}
@@ -0,0 +1,90 @@
+use core::alloc::Layout;
+use core::ptr;
+
+use bumpalo::{boxed::Box, Bump};
+
+use crate::*;
+
+/// Deallocation is a no-op, as the memory is freed along with the arena.
+unsafe impl<'a, T: ?Sized> RawDerefTake for Box<'a, T> {
+    type DeallocInfo = ();
+
+    fn into_raw_parts(self) -> (*mut T, ()) {
+        (Box::into_raw(self), ())
+    }
+
+    unsafe fn dealloc(_: *mut T, _: ()) {
+    }
+}
+
+unsafe impl<'a, T: ?Sized> Take<T> for Box<'a, T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+crate::takeorclone::impl_take_or_clone!(['a, T: ?Sized] Box<'a, T> => T);
+crate::fallible::impl_try_take!(['a, T: ?Sized] Box<'a, T> => T);
+
+/// Taking values into a `bumpalo` arena.
+pub trait BumpTakeExt : DerefTake + Sized {
+    /// Moves the value out of the container into arena storage.
+    ///
+    /// Works for unsized values too, which are copied into an allocation of the same layout.
+    fn take_in(self, bump: &Bump) -> Box<'_, Self::Target> {
+        self.deref_take_unsized(|src| unsafe {
+            let src = TakeSlot::into_raw(src);
+            let layout = Layout::for_value::<Self::Target>(src);
+            let src: *mut Self::Target = &mut **src;
+
+            let dst = bump.alloc_layout(layout).as_ptr();
+            ptr::copy_nonoverlapping(src as *const u8, dst, layout.size());
+            Box::from_raw(raw::with_addr_of(src, dst))
+        })
+    }
+}
+
+impl<C: DerefTake> BumpTakeExt for C {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_box() {
+        let check = DropCheck::new();
+        let bump = Bump::new();
+
+        let (token, state) = check.pair();
+        let boxed = Box::new_in(token, &bump);
+        let token = boxed.deref_take();
+        assert!(state.is_not_dropped());
+        drop(token);
+        assert!(state.is_dropped());
+
+        let boxed: Box<[u8]> = unsafe { Box::from_raw(bump.alloc_slice_copy(&[1, 2, 3])) };
+        assert_eq!(Take::<[u8]>::take_owned(boxed), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_take_in() {
+        let check = DropCheck::new();
+        let bump = Bump::new();
+
+        let (token, state) = check.pair();
+        let in_arena = std::boxed::Box::new(token).take_in(&bump);
+        assert!(state.is_not_dropped());
+        drop(in_arena);
+        assert!(state.is_dropped());
+
+        let s = String::from("arena").take_in(&bump);
+        assert_eq!(&*s, "arena");
+        let v = vec![String::from("a"), String::from("b")].take_in(&bump);
+        assert_eq!(&*v, ["a", "b"]);
+    }
+}
@@ -0,0 +1,73 @@
+use alloc::vec::Vec;
+
+use bytes::{Bytes, BytesMut};
+
+use crate::*;
+
+/// Reclaims the underlying buffer if the `Bytes` is unique, and copies otherwise.
+unsafe impl Take<[u8]> for Bytes {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [u8]>) -> R
+    {
+        Vec::from(self).deref_take_unsized(f)
+    }
+}
+
+/// Reclaims the underlying buffer if the `BytesMut` is unique, and copies otherwise.
+unsafe impl Take<[u8]> for BytesMut {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [u8]>) -> R
+    {
+        Vec::from(self).deref_take_unsized(f)
+    }
+}
+
+/// Fails if the `Bytes` isn't unique, such as when it's shared or static, returning it.
+unsafe impl TryTake<[u8]> for Bytes {
+    type Error = Self;
+
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, [u8]>) -> R
+    {
+        if self.is_unique() {
+            Ok(self.take_unsized(f))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+crate::takeorclone::impl_take_or_clone!([] Bytes => [u8]);
+crate::takeorclone::impl_take_or_clone!([] BytesMut => [u8]);
+crate::fallible::impl_try_take!([] BytesMut => [u8]);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bytes() {
+        let v = b"hello".to_vec();
+        let ptr = v.as_ptr();
+        let bytes = Bytes::from(v);
+        let bytes2 = bytes.clone();
+
+        let bytes = TryTake::<[u8]>::try_take_owned(bytes).unwrap_err();
+        drop(bytes2);
+        let taken = TryTake::<[u8]>::try_take_unsized(bytes, |src| src.as_ptr()).unwrap();
+        assert_eq!(taken, ptr);
+
+        let bytes = Bytes::from_static(b"static");
+        let bytes = TryTake::<[u8]>::try_take_owned(bytes).unwrap_err();
+        assert_eq!(Take::<[u8]>::take_owned(bytes), b"static");
+    }
+
+    #[test]
+    fn test_bytes_mut() {
+        let mut buf = BytesMut::with_capacity(16);
+        buf.extend_from_slice(b"hello world");
+        let world = buf.split_off(6);
+        assert_eq!(Take::<[u8]>::take_owned(buf), b"hello ");
+        assert_eq!(Take::<[u8]>::take_owned(world), b"world");
+    }
+}
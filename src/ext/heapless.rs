@@ -0,0 +1,103 @@
+use core::mem::{self, ManuallyDrop};
+use core::slice;
+
+use heapless::{String, Vec};
+
+use crate::*;
+
+unsafe impl<T, const N: usize> DerefTake for Vec<T, N> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(mut self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        unsafe {
+            let len = self.len();
+
+            // As with Vec, a len of 0 means a panic won't call drop on any of the elements.
+            self.set_len(0);
+            let src: &mut [T] = slice::from_raw_parts_mut(self.as_mut_ptr(), len);
+            TakeSlot::scoped(mem::transmute::<&mut [T], &mut ManuallyDrop<[T]>>(src), f)
+        }
+    }
+}
+
+crate::dereftake::impl_deref_take_for_pin!([T, const N: usize] Vec<T, N>);
+
+unsafe impl<T, const N: usize> Take<[T]> for Vec<T, N> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+unsafe impl<const N: usize> DerefTake for String<N> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        self.into_bytes().deref_take_unsized(|src| {
+            // str has the same layout as [u8]
+            let src = TakeSlot::into_raw(src) as *mut ManuallyDrop<[u8]> as *mut ManuallyDrop<str>;
+            unsafe { TakeSlot::scoped(&mut *src, f) }
+        })
+    }
+}
+
+crate::dereftake::impl_deref_take_for_pin!([const N: usize] String<N>);
+
+unsafe impl<const N: usize> Take<str> for String<N> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, str>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+crate::takeorclone::impl_take_or_clone!([T, const N: usize] Vec<T, N> => [T]);
+#[cfg(feature = "alloc")]
+crate::takeorclone::impl_take_or_clone!([const N: usize] String<N> => str);
+crate::fallible::impl_try_take!([T, const N: usize] Vec<T, N> => [T]);
+crate::fallible::impl_try_take!([const N: usize] String<N> => str);
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use core::convert::TryFrom;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    #[test]
+    fn test_vec() {
+        let check = DropCheck::new();
+
+        let mut v: Vec<DropToken, 8> = Vec::new();
+        for _ in 0 .. 3 {
+            assert!(v.push(check.token()).is_ok());
+        }
+        let taken: std::vec::Vec<DropToken> = v.deref_take();
+        assert_eq!(taken.len(), 3);
+        assert!(check.none_dropped());
+        drop(taken);
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_string() {
+        let s: String<16> = String::try_from("heapless").unwrap();
+        let taken: std::string::String = Take::<str>::take_owned(s);
+        assert_eq!(taken, "heapless");
+    }
+}
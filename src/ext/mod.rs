@@ -0,0 +1,29 @@
+//! Impls for types from other crates, each behind a feature of the same name.
+
+#[cfg(feature = "smallvec")]
+mod smallvec;
+
+#[cfg(feature = "bytes")]
+mod bytes;
+
+#[cfg(feature = "triomphe")]
+mod triomphe;
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec;
+
+#[cfg(feature = "tinyvec")]
+mod tinyvec;
+
+#[cfg(feature = "heapless")]
+mod heapless;
+
+#[cfg(feature = "bumpalo")]
+mod bumpalo;
+#[cfg(feature = "bumpalo")]
+pub use self::bumpalo::BumpTakeExt;
+
+#[cfg(feature = "rayon")]
+mod rayon;
+#[cfg(feature = "rayon")]
+pub use self::rayon::ParTakeExt;
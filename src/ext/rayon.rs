@@ -0,0 +1,167 @@
+use core::mem;
+use core::ops;
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use std::vec::Vec;
+
+use rayon::prelude::*;
+
+use crate::*;
+
+/// Parallel element-wise taking for slice containers.
+pub trait ParTakeExt : DerefTake + Sized {
+    /// Moves the elements out in parallel, mapping each one with `f`.
+    ///
+    /// The order of the elements is preserved. If `f` panics the elements that weren't taken yet
+    /// are dropped, as are the results produced so far, and the panic is propagated.
+    fn par_take_map<T, U, F>(self, f: F) -> Vec<U>
+        where Self: ops::Deref<Target = [T]>,
+              T: Send,
+              U: Send,
+              F: Fn(T) -> U + Sync
+    {
+        self.deref_take_unsized(|src| {
+            let src = TakeSlot::into_raw(src);
+            let len = src.len();
+            let mut dst: Vec<U> = Vec::with_capacity(len);
+
+            let chunk_len = (len / (rayon::current_num_threads() * 4)).max(1);
+            let num_chunks = len.div_ceil(chunk_len);
+
+            let guard = Guard {
+                src: SendPtr(src.as_mut_ptr()),
+                dst: SendPtr(dst.as_mut_ptr()),
+                len,
+                chunk_len,
+                progress: (0 .. num_chunks).map(|_| Progress::default()).collect(),
+            };
+
+            (0 .. num_chunks).into_par_iter().for_each(|i| {
+                let (src, dst) = (guard.src.get(), guard.dst.get());
+                let progress = &guard.progress[i];
+                let start = i * chunk_len;
+                let end = (start + chunk_len).min(len);
+
+                for j in start .. end {
+                    unsafe {
+                        let item = src.add(j).read();
+                        progress.taken.store(j - start + 1, Ordering::Relaxed);
+                        dst.add(j).write(f(item));
+                        progress.written.store(j - start + 1, Ordering::Relaxed);
+                    }
+                }
+            });
+
+            // Everything was taken and written, so there's nothing for the guard to clean up.
+            mem::forget(guard);
+            unsafe { dst.set_len(len) };
+            dst
+        })
+    }
+
+    /// Moves the elements out in parallel, calling `f` on each one.
+    ///
+    /// If `f` panics the elements that weren't taken yet are dropped, and the panic is propagated.
+    fn par_take_each<T, F>(self, f: F)
+        where Self: ops::Deref<Target = [T]>,
+              T: Send,
+              F: Fn(T) + Sync
+    {
+        // Vec<()> doesn't allocate.
+        self.par_take_map(f);
+    }
+}
+
+impl<C: DerefTake> ParTakeExt for C {}
+
+struct SendPtr<T>(*mut T);
+
+unsafe impl<T: Send> Send for SendPtr<T> {}
+unsafe impl<T: Send> Sync for SendPtr<T> {}
+
+impl<T> SendPtr<T> {
+    fn get(&self) -> *mut T {
+        self.0
+    }
+}
+
+#[derive(Default)]
+struct Progress {
+    taken: AtomicUsize,
+    written: AtomicUsize,
+}
+
+// Only dropped if a panic occurs, after rayon has finished with every chunk.
+struct Guard<T, U> {
+    src: SendPtr<T>,
+    dst: SendPtr<U>,
+    len: usize,
+    chunk_len: usize,
+    progress: Vec<Progress>,
+}
+
+impl<T, U> Drop for Guard<T, U> {
+    fn drop(&mut self) {
+        for (i, progress) in self.progress.iter().enumerate() {
+            let start = i * self.chunk_len;
+            let end = (start + self.chunk_len).min(self.len);
+            let taken = progress.taken.load(Ordering::Relaxed);
+            let written = progress.written.load(Ordering::Relaxed);
+
+            unsafe {
+                let rest = self.src.get().add(start + taken);
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(rest, end - start - taken));
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.dst.get().add(start), written));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_par_take_map() {
+        let v: Vec<String> = (0 .. 10_000).map(|i| i.to_string()).collect();
+        let lens = v.par_take_map(|s| s.len());
+        assert_eq!(lens.len(), 10_000);
+        assert_eq!(lens[9_999], 4);
+
+        let boxed: Box<[u32]> = (0 .. 1000).collect();
+        let doubled = boxed.par_take_map(|x| x * 2);
+        assert!(doubled.iter().enumerate().all(|(i, x)| *x == i as u32 * 2));
+
+        let empty: Vec<String> = Vec::new();
+        assert!(empty.par_take_map(|s| s).is_empty());
+    }
+
+    #[test]
+    fn test_par_take_each() {
+        let count = AtomicUsize::new(0);
+        let arc: Arc<[u8]> = Arc::from(vec![1; 1000]);
+        arc.par_take_each(|x| { count.fetch_add(x as usize, Ordering::Relaxed); });
+        assert_eq!(count.into_inner(), 1000);
+    }
+
+    #[test]
+    fn test_panic() {
+        let rc = Arc::new(());
+        let v: Vec<(usize, Arc<()>)> = (0 .. 1000).map(|i| (i, Arc::clone(&rc))).collect();
+
+        let r = panic::catch_unwind(AssertUnwindSafe(|| {
+            v.par_take_map(|(i, x)| {
+                if i == 500 {
+                    panic!();
+                }
+                x
+            })
+        }));
+        assert!(r.is_err());
+        assert_eq!(Arc::strong_count(&rc), 1);
+    }
+}
@@ -0,0 +1,110 @@
+use core::mem::{self, ManuallyDrop};
+use core::slice;
+
+use smallvec::{Array, SmallVec};
+
+use crate::*;
+
+/// Moves inline elements out directly. Spilled elements reuse the heap allocation.
+unsafe impl<A: Array> DerefTake for SmallVec<A> {
+    fn deref_take(self) -> <[A::Item] as IntoOwned>::Owned {
+        if self.spilled() {
+            self.into_vec()
+        } else {
+            self.deref_take_unsized(|src| src.into_owned())
+        }
+    }
+
+    fn deref_take_unsized<F, R>(mut self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        unsafe {
+            let len = self.len();
+
+            // As with Vec, a len of 0 means a panic won't call drop on any of the elements, while
+            // the memory, inline or not, stays valid until self is dropped.
+            self.set_len(0);
+            let src: &mut [A::Item] = slice::from_raw_parts_mut(self.as_mut_ptr(), len);
+            TakeSlot::scoped(mem::transmute::<&mut [A::Item], &mut ManuallyDrop<[A::Item]>>(src), f)
+        }
+    }
+}
+
+crate::dereftake::impl_deref_take_for_pin!([A: Array] SmallVec<A>);
+
+unsafe impl<A: Array> Take<[A::Item]> for SmallVec<A> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [A::Item]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+crate::takeorclone::impl_take_or_clone!([A: Array] SmallVec<A> => [A::Item]);
+crate::fallible::impl_try_take!([A: Array] SmallVec<A> => [A::Item]);
+
+/// Reuses the allocation if spilled; inline elements are moved to the heap first.
+impl<A: Array> IntoTakeIter for SmallVec<A> {
+    type Item = A::Item;
+    type Storage = RawStorage<alloc::vec::Vec<A::Item>>;
+
+    fn take_iter(self) -> TakeIter<A::Item, Self::Storage> {
+        self.into_vec().take_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use core::pin::Pin;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    #[test]
+    fn test_inline() {
+        let check = DropCheck::new();
+
+        let v: SmallVec<[DropToken; 4]> = (0 .. 3).map(|_| check.token()).collect();
+        assert!(!v.spilled());
+        let taken = v.deref_take();
+        assert_eq!(taken.len(), 3);
+        assert!(check.none_dropped());
+        drop(taken);
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_pin() {
+        let v: SmallVec<[String; 2]> = SmallVec::from_elem(String::from("x"), 1);
+        assert_eq!(Pin::new(v).deref_take(), ["x"]);
+    }
+
+    #[test]
+    fn test_take_iter() {
+        let v: SmallVec<[String; 2]> = ["a", "b", "c"].iter().map(|s| String::from(*s)).collect();
+        let mut iter = v.take_iter();
+        assert_eq!(iter.next_back().unwrap(), "c");
+        assert_eq!(iter.collect::<Vec<_>>(), ["a", "b"]);
+
+        let v: SmallVec<[String; 2]> = SmallVec::from_elem(String::from("x"), 1);
+        assert_eq!(v.take_iter().next().unwrap(), "x");
+    }
+
+    #[test]
+    fn test_spilled() {
+        let check = DropCheck::new();
+
+        let v: SmallVec<[DropToken; 2]> = (0 .. 3).map(|_| check.token()).collect();
+        assert!(v.spilled());
+        let ptr = v.as_ptr();
+        let taken = v.deref_take();
+        assert_eq!(taken.as_ptr(), ptr);
+        assert!(check.none_dropped());
+        drop(taken);
+        assert!(check.all_dropped());
+
+        let v: SmallVec<[u8; 1]> = SmallVec::from_slice(b"abc");
+        assert_eq!(Take::<[u8]>::take_unsized(v, |src| src.len()), 3);
+    }
+}
@@ -0,0 +1,131 @@
+use core::mem::{self, ManuallyDrop};
+use core::ptr;
+
+use tinyvec::{Array, ArrayVec};
+#[cfg(feature = "alloc")]
+use tinyvec::TinyVec;
+
+use crate::*;
+
+/// tinyvec keeps every slot of the array initialized, so the slots past the length are dropped,
+/// while the elements themselves are moved out.
+unsafe impl<A: Array> DerefTake for ArrayVec<A> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        let len = self.len();
+        let mut data = ManuallyDrop::new(self.into_inner());
+        let (src, unused) = data.as_slice_mut().split_at_mut(len);
+
+        unsafe {
+            ptr::drop_in_place(unused);
+            TakeSlot::scoped(mem::transmute::<&mut [A::Item], &mut ManuallyDrop<[A::Item]>>(src), f)
+        }
+    }
+}
+
+crate::dereftake::impl_deref_take_for_pin!([A: Array] ArrayVec<A>);
+
+unsafe impl<A: Array> Take<[A::Item]> for ArrayVec<A> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [A::Item]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+/// Reuses the allocation if the `TinyVec` is on the heap.
+#[cfg(feature = "alloc")]
+unsafe impl<A: Array> DerefTake for TinyVec<A> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        match self {
+            TinyVec::Inline(inline) => inline.deref_take_unsized(f),
+            TinyVec::Heap(heap) => heap.deref_take_unsized(f),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+crate::dereftake::impl_deref_take_for_pin!([A: Array] TinyVec<A>);
+
+#[cfg(feature = "alloc")]
+unsafe impl<A: Array> Take<[A::Item]> for TinyVec<A> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [A::Item]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+crate::takeorclone::impl_take_or_clone!([A: Array] ArrayVec<A> => [A::Item]);
+crate::fallible::impl_try_take!([A: Array] ArrayVec<A> => [A::Item]);
+#[cfg(feature = "alloc")]
+crate::takeorclone::impl_take_or_clone!([A: Array] TinyVec<A> => [A::Item]);
+#[cfg(feature = "alloc")]
+crate::fallible::impl_try_take!([A: Array] TinyVec<A> => [A::Item]);
+
+/// Reuses the allocation if the `TinyVec` is on the heap; inline elements are moved to the heap
+/// first.
+#[cfg(feature = "alloc")]
+impl<A: Array> IntoTakeIter for TinyVec<A> {
+    type Item = A::Item;
+    type Storage = RawStorage<alloc::vec::Vec<A::Item>>;
+
+    fn take_iter(self) -> TakeIter<A::Item, Self::Storage> {
+        match self {
+            TinyVec::Inline(inline) => inline.into_iter().collect::<alloc::vec::Vec<_>>().take_iter(),
+            TinyVec::Heap(heap) => heap.take_iter(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use std::rc::Rc;
+
+    #[test]
+    fn test_arrayvec() {
+        let rc = Rc::new(());
+        let mut v: ArrayVec<[Option<Rc<()>>; 4]> = ArrayVec::new();
+        v.push(Some(Rc::clone(&rc)));
+        v.push(Some(Rc::clone(&rc)));
+
+        let taken: Vec<Option<Rc<()>>> = v.deref_take();
+        assert_eq!(Rc::strong_count(&rc), 3);
+        drop(taken);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_tinyvec() {
+        let v: TinyVec<[String; 2]> = ["a"].iter().map(|s| String::from(*s)).collect();
+        assert!(v.is_inline());
+        assert_eq!(Take::<[String]>::take_owned(v), ["a"]);
+
+        let v: TinyVec<[String; 2]> = ["a", "b", "c"].iter().map(|s| String::from(*s)).collect();
+        assert!(v.is_heap());
+        let ptr = v.as_ptr();
+        let taken = v.deref_take_unsized(|src| {
+            assert_eq!(src.as_ptr(), ptr);
+            src.into_owned()
+        });
+        assert_eq!(taken, ["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,156 @@
+use core::mem::ManuallyDrop;
+
+use alloc::string::String;
+
+use triomphe::Arc;
+
+use crate::*;
+
+// Same strategy as the std Arc impls: casting to Arc<ManuallyDrop<T>> means dropping the Arc
+// won't drop the contents.
+
+unsafe impl<T: ?Sized> TryDerefTake for Arc<T> {
+    fn try_deref_take_unsized<F, R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        let mut this: Arc<ManuallyDrop<T>> = unsafe { Arc::from_raw(Arc::into_raw(self) as *const _) };
+
+        match Arc::get_mut(&mut this) {
+            Some(unique) => Ok(unsafe { TakeSlot::scoped(unique, f) }),
+            None => Err(unsafe { Arc::from_raw(Arc::into_raw(this) as *const T) }),
+        }
+    }
+}
+
+unsafe impl<T: Clone> DerefTake for Arc<T> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        let mut this: Arc<ManuallyDrop<T>> = unsafe { Arc::from_raw(Arc::into_raw(self) as *const _) };
+        unsafe { TakeSlot::scoped(Arc::make_mut(&mut this), f) }
+    }
+}
+
+unsafe impl<T: Clone> DerefTake for Arc<[T]> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        let mut f = Some(f);
+        match self.try_deref_take_unsized(|src| f.take().unwrap()(src)) {
+            Ok(r) => r,
+            Err(shared) => shared.to_vec().take_unsized(f.take().unwrap()),
+        }
+    }
+}
+
+unsafe impl DerefTake for Arc<str> {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        let mut f = Some(f);
+        match self.try_deref_take_unsized(|src| f.take().unwrap()(src)) {
+            Ok(r) => r,
+            Err(shared) => String::from(&*shared).take_unsized(f.take().unwrap()),
+        }
+    }
+}
+
+crate::dereftake::impl_deref_take_for_pin!([T: Clone] Arc<T>);
+crate::dereftake::impl_deref_take_for_pin!([T: Clone] Arc<[T]>);
+crate::dereftake::impl_deref_take_for_pin!([] Arc<str>);
+
+unsafe impl<T: Clone> Take<T> for Arc<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+unsafe impl<T: Clone> Take<[T]> for Arc<[T]> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+unsafe impl Take<str> for Arc<str> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, str>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+/// Fails if the `Arc` isn't the only owner, returning it.
+unsafe impl<T: ?Sized> TryTake<T> for Arc<T> {
+    type Error = Self;
+
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.try_deref_take_unsized(f)
+    }
+}
+
+crate::takeorclone::impl_take_or_clone!([T] Arc<T> => T);
+crate::takeorclone::impl_take_or_clone!([T] Arc<[T]> => [T]);
+crate::takeorclone::impl_take_or_clone!([] Arc<str> => str);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_arc() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let arc = Arc::new(token);
+        let arc2 = Arc::clone(&arc);
+        let arc = arc.try_deref_take().err().unwrap();
+        drop(arc2);
+        let token = arc.try_deref_take().ok().unwrap();
+        assert!(state.is_not_dropped());
+        drop(token);
+        assert!(state.is_dropped());
+
+        let arc = Arc::new(String::from("hello"));
+        let arc2 = Arc::clone(&arc);
+        assert_eq!(arc.deref_take(), "hello");
+        assert_eq!(*arc2, "hello");
+    }
+
+    #[test]
+    fn test_unsized() {
+        let arc: Arc<[String]> = Arc::from(vec![String::from("a"), String::from("b")]);
+        let arc2 = Arc::clone(&arc);
+        let v: Vec<String> = arc.deref_take();
+        assert_eq!(v, ["a", "b"]);
+        assert_eq!(Take::<[String]>::take_owned(arc2), ["a", "b"]);
+
+        let arc: Arc<str> = Arc::from("hello");
+        assert_eq!(TryTake::<str>::try_take_owned(arc).unwrap(), "hello");
+    }
+}
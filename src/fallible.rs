@@ -0,0 +1,372 @@
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
+use core::mem::ManuallyDrop;
+
+#[cfg(feature = "alloc")]
+use core::{ffi::CStr, pin::Pin};
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    collections::{BinaryHeap, LinkedList, VecDeque},
+    ffi::CString,
+    rc::Rc,
+    string::String,
+    sync::Arc,
+    vec::{self, Vec},
+};
+
+#[cfg(feature = "allocator_api")]
+use alloc::alloc::Allocator;
+
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
+use std::sync::{Mutex, PoisonError, RwLock};
+
+use super::*;
+
+/// Fallible conversion from unsized to sized.
+///
+/// Every `IntoOwned` type is also `TryIntoOwned`, with `Infallible` as the error.
+///
+/// # Safety
+///
+/// Same as `IntoOwned`. Additionally, if the conversion fails the value must be left untouched.
+pub unsafe trait TryIntoOwned {
+    /// The resulting `Sized` type after conversion.
+    type Owned;
+
+    /// The error returned if the conversion fails.
+    type Error;
+
+    /// Tries to perform the conversion.
+    ///
+    /// # Safety
+    ///
+    /// Same as `IntoOwned::into_owned_unchecked()` if the conversion succeeds. If it fails, the
+    /// value is still valid and owned by the caller.
+    unsafe fn try_into_owned_unchecked(this: &mut ManuallyDrop<Self>) -> Result<Self::Owned, Self::Error>;
+}
+
+unsafe impl<T: ?Sized + IntoOwned> TryIntoOwned for T {
+    type Owned = T::Owned;
+    type Error = Infallible;
+
+    unsafe fn try_into_owned_unchecked(this: &mut ManuallyDrop<Self>) -> Result<Self::Owned, Infallible> {
+        Ok(T::into_owned_unchecked(this))
+    }
+}
+
+/// Fallible `Take`.
+///
+/// Implemented for containers where taking can fail, such as shared `Rc`s and poisoned locks, as
+/// well as infallibly, with `Infallible` as the error, for every other `Take` container, so APIs
+/// can accept both.
+///
+/// # Safety
+///
+/// This trait is unsafe to implement because `try_take_unsized()` must not `drop()` or otherwise
+/// use the taken value after the closure returns.
+pub unsafe trait TryTake<T: ?Sized> : Sized {
+    /// The error returned if ownership can't be taken.
+    type Error;
+
+    /// Tries to take ownership of `Sized` type.
+    fn try_take_sized(self) -> Result<T, Self::Error>
+        where T: Sized
+    {
+        self.try_take_unsized(|src| src.read())
+    }
+
+    /// Tries to take ownership of the owned version of an unsized type.
+    fn try_take_owned(self) -> Result<T::Owned, Self::Error>
+        where T: IntoOwned
+    {
+        self.try_take_unsized(|src| src.into_owned())
+    }
+
+    /// Tries to take ownership of an unsized type with the aid of a closure.
+    ///
+    /// If ownership can be taken, the closure is called as per `Take::take_unsized()`. Otherwise
+    /// the closure is not called.
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, Self::Error>
+        where F: FnOnce(TakeSlot<'_, T>) -> R;
+}
+
+/// Implements `TryTake` for a container in terms of its `Take` impl, with `Infallible` as the
+/// error.
+///
+/// Exported via `__private` for `#[derive(Take)]`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_try_take {
+    ($(#[$attr:meta])* [$($g:tt)*] $ty:ty => $t:ty $(where $($w:tt)*)?) => {
+        $(#[$attr])*
+        unsafe impl<$($g)*> $crate::TryTake<$t> for $ty
+            where $ty: $crate::Take<$t>,
+                  $($($w)*)?
+        {
+            type Error = ::core::convert::Infallible;
+
+            fn try_take_unsized<F, R>(self, f: F) -> ::core::result::Result<R, Self::Error>
+                where F: FnOnce($crate::TakeSlot<'_, $t>) -> R
+            {
+                Ok($crate::Take::<$t>::take_unsized(self, f))
+            }
+        }
+    };
+}
+pub(crate) use crate::__impl_try_take as impl_try_take;
+
+impl_try_take!([T] T => T);
+impl_try_take!([T] ManuallyDrop<T> => T);
+impl_try_take!([T] Cell<T> => T);
+impl_try_take!([T] RefCell<T> => T);
+impl_try_take!(#[cfg(all(feature = "alloc", not(feature = "allocator_api")))] [T: ?Sized] Box<T> => T);
+impl_try_take!(#[cfg(feature = "allocator_api")] [T: ?Sized, A: Allocator] Box<T, A> => T);
+impl_try_take!(#[cfg(all(feature = "alloc", not(feature = "allocator_api")))] [T: ?Sized] Pin<Box<T>> => T);
+impl_try_take!(#[cfg(feature = "allocator_api")] [T: ?Sized, A: Allocator] Pin<Box<T, A>> => T);
+impl_try_take!([T, const N: usize] [T; N] => [T]);
+impl_try_take!(#[cfg(feature = "alloc")] [T, const N: usize] Box<[T; N]> => [T]);
+impl_try_take!(#[cfg(all(feature = "alloc", not(feature = "allocator_api")))] [T] Vec<T> => [T]);
+impl_try_take!(#[cfg(feature = "allocator_api")] [T, A: Allocator] Vec<T, A> => [T]);
+impl_try_take!(#[cfg(feature = "alloc")] [T] VecDeque<T> => [T]);
+impl_try_take!(#[cfg(feature = "alloc")] [T] BinaryHeap<T> => [T]);
+impl_try_take!(#[cfg(feature = "alloc")] [T] LinkedList<T> => [T]);
+impl_try_take!(#[cfg(feature = "alloc")] [T] vec::IntoIter<T> => [T]);
+impl_try_take!(#[cfg(feature = "alloc")] ['a, T] vec::Drain<'a, T> => [T]);
+impl_try_take!(#[cfg(feature = "alloc")] ['a, B: ?Sized + ToOwned] Cow<'a, B> => B);
+impl_try_take!(#[cfg(feature = "alloc")] [] String => str);
+impl_try_take!(#[cfg(feature = "alloc")] [] CString => CStr);
+impl_try_take!(#[cfg(feature = "std")] [] OsString => OsStr);
+impl_try_take!(#[cfg(feature = "std")] [] PathBuf => Path);
+impl_try_take!(#[cfg(feature = "alloc")] ['a, T: ?Sized + IntoOwned] TakeCow<'a, T> => T);
+impl_try_take!([T: ?Sized] ForeignBox<T> => T);
+impl_try_take!([T] TakeOnce<T> => T);
+impl_try_take!([T] AtomicTakeOnce<T> => T);
+
+/// Fails if the value is `None`.
+unsafe impl<T> TryTake<T> for Option<T> {
+    type Error = Self;
+
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        match self {
+            Some(value) => Ok(value.take_unsized(f)),
+            None => Err(None),
+        }
+    }
+}
+
+/// Fails if the value is an `Err`, returning the `Result`.
+unsafe impl<T, E> TryTake<T> for Result<T, E> {
+    type Error = Self;
+
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        match self {
+            Ok(value) => Ok(value.take_unsized(f)),
+            Err(err) => Err(Err(err)),
+        }
+    }
+}
+
+/// Fails if the length isn't `N`, returning the `Vec`.
+#[cfg(feature = "alloc")]
+unsafe impl<T, const N: usize> TryTake<[T; N]> for Vec<T> {
+    type Error = Self;
+
+    fn try_take_unsized<F,R>(mut self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, [T; N]>) -> R
+    {
+        if self.len() != N {
+            return Err(self);
+        }
+
+        unsafe {
+            // The elements now belong to the slot, which is taken from in place; dropping self
+            // afterwards only frees the memory.
+            self.set_len(0);
+            let array = &mut *(self.as_mut_ptr() as *mut ManuallyDrop<[T; N]>);
+            Ok(TakeSlot::scoped(array, f))
+        }
+    }
+}
+
+/// Fails if the length isn't `N`, returning the `Box`.
+#[cfg(feature = "alloc")]
+unsafe impl<T, const N: usize> TryTake<[T; N]> for Box<[T]> {
+    type Error = Self;
+
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, [T; N]>) -> R
+    {
+        if self.len() != N {
+            return Err(self);
+        }
+
+        match self.into_vec().try_take_unsized(f) {
+            Ok(r) => Ok(r),
+            Err(_) => unreachable!("length was checked"),
+        }
+    }
+}
+
+/// Fails if the `Rc` isn't the only owner, returning it.
+#[cfg(feature = "alloc")]
+unsafe impl<T: ?Sized> TryTake<T> for Rc<T> {
+    type Error = Self;
+
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.try_deref_take_unsized(f)
+    }
+}
+
+/// Fails if the `Arc` isn't the only owner, returning it.
+#[cfg(feature = "alloc")]
+unsafe impl<T: ?Sized> TryTake<T> for Arc<T> {
+    type Error = Self;
+
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.try_deref_take_unsized(f)
+    }
+}
+
+/// Fails if the lock is poisoned; the error still contains the value.
+#[cfg(feature = "std")]
+unsafe impl<T> TryTake<T> for Mutex<T> {
+    type Error = PoisonError<T>;
+
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, PoisonError<T>>
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.into_inner().map(|inner| ManuallyDrop::new(inner).deref_take_unsized(f))
+    }
+}
+
+/// Fails if the lock is poisoned; the error still contains the value.
+#[cfg(feature = "std")]
+unsafe impl<T> TryTake<T> for RwLock<T> {
+    type Error = PoisonError<T>;
+
+    fn try_take_unsized<F,R>(self, f: F) -> Result<R, PoisonError<T>>
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.into_inner().map(|inner| ManuallyDrop::new(inner).deref_take_unsized(f))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    use std::thread;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    fn try_take_string<C: TryTake<str>>(src: C) -> Result<String, C::Error> {
+        src.try_take_owned()
+    }
+
+    #[test]
+    fn test_infallible() {
+        let r: Result<String, Infallible> = String::from("sized").try_take_sized();
+        assert_eq!(r.unwrap(), "sized");
+
+        let boxed: Box<str> = "boxed".into();
+        assert_eq!(try_take_string(boxed).unwrap(), "boxed");
+
+        let r = TryTake::<[u8]>::try_take_owned(Vec::from(&b"vec"[..]));
+        assert_eq!(r.unwrap(), b"vec");
+    }
+
+    #[test]
+    fn test_array() {
+        let check = DropCheck::new();
+
+        let v: Vec<DropToken> = (0 .. 3).map(|_| check.token()).collect();
+        let v = TryTake::<[DropToken; 2]>::try_take_sized(v).unwrap_err();
+        assert_eq!(v.len(), 3);
+
+        let array: [DropToken; 3] = v.try_take_sized().unwrap();
+        assert!(check.none_dropped());
+        drop(array);
+        assert!(check.all_dropped());
+
+        let boxed: Box<[u8]> = vec![1, 2, 3].into_boxed_slice();
+        let boxed = TryTake::<[u8; 4]>::try_take_sized(boxed).unwrap_err();
+        let array: [u8; 3] = boxed.try_take_sized().unwrap();
+        assert_eq!(array, [1, 2, 3]);
+
+        let empty: [String; 0] = Vec::new().try_take_sized().unwrap();
+        assert_eq!(empty.len(), 0);
+    }
+
+    #[test]
+    fn test_option_result() {
+        let r: Result<String, _> = Some(String::from("some")).try_take_sized();
+        assert_eq!(r.unwrap(), "some");
+        assert_eq!(TryTake::<String>::try_take_sized(None).unwrap_err(), None);
+
+        let r = TryTake::<String>::try_take_sized(Err::<String, u8>(42));
+        assert_eq!(r.unwrap_err(), Err(42));
+    }
+
+    #[test]
+    fn test_rc() {
+        let rc: Rc<str> = Rc::from("hello");
+        let rc2 = Rc::clone(&rc);
+        let rc = try_take_string(rc).unwrap_err();
+        drop(rc2);
+        assert_eq!(try_take_string(rc).unwrap(), "hello");
+
+        let arc: Arc<String> = Arc::new(String::from("hello"));
+        assert_eq!(TryTake::<String>::try_take_sized(arc).unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_poisoned() {
+        let mutex = Arc::new(Mutex::new(String::from("poisoned")));
+        let mutex2 = Arc::clone(&mutex);
+        let _ = thread::spawn(move || {
+            let _guard = mutex2.lock().unwrap();
+            panic!();
+        }).join();
+
+        let mutex = Arc::try_unwrap(mutex).unwrap();
+        let err = TryTake::<String>::try_take_sized(mutex).unwrap_err();
+        assert_eq!(err.into_inner(), "poisoned");
+
+        let lock = RwLock::new(String::from("fine"));
+        assert_eq!(TryTake::<String>::try_take_sized(lock).unwrap(), "fine");
+    }
+
+    #[test]
+    fn test_try_into_owned() {
+        let mut s = ManuallyDrop::new(String::from("hello"));
+        let r = unsafe { String::try_into_owned_unchecked(&mut s) };
+        assert_eq!(r.unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_bridged() {
+        let r = TryTake::<str>::try_take_owned(String::from("string"));
+        assert_eq!(r.unwrap(), "string");
+
+        let r = TryTake::<u8>::try_take_sized(Cell::new(1));
+        assert_eq!(r.unwrap(), 1);
+
+        let r = TryTake::<[u8]>::try_take_owned(VecDeque::from(vec![1, 2]));
+        assert_eq!(r.unwrap(), [1, 2]);
+    }
+}
@@ -0,0 +1,149 @@
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops;
+use core::ptr::{self, NonNull};
+
+use super::*;
+
+/// An owned value allocated by foreign code, such as a C library.
+///
+/// Like `Box<T>`, except the memory is freed with a deallocator supplied along with the pointer.
+/// The deallocator must only free the memory: the value itself is dropped, or taken, separately.
+///
+/// `ForeignBox` isn't `Send` or `Sync`, as nothing is known about the thread-safety of the
+/// deallocator.
+pub struct ForeignBox<T: ?Sized> {
+    ptr: NonNull<T>,
+    dealloc: unsafe fn(*mut T),
+}
+
+impl<T: ?Sized> ForeignBox<T> {
+    /// Creates a `ForeignBox` from a raw pointer and a deallocator.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null, properly aligned, point to a valid `T` the caller owns, and remain
+    /// valid until it's passed to `dealloc`. `dealloc` must free the memory without dropping the
+    /// value.
+    pub unsafe fn from_raw(ptr: *mut T, dealloc: unsafe fn(*mut T)) -> Self {
+        ForeignBox {
+            ptr: NonNull::new_unchecked(ptr),
+            dealloc,
+        }
+    }
+
+    /// Consumes the `ForeignBox`, returning the raw pointer and deallocator.
+    pub fn into_raw(this: Self) -> (*mut T, unsafe fn(*mut T)) {
+        let this = ManuallyDrop::new(this);
+        (this.ptr.as_ptr(), this.dealloc)
+    }
+}
+
+impl<T: ?Sized> ops::Deref for ForeignBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: ?Sized> ops::DerefMut for ForeignBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for ForeignBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ForeignBox").field(&&**self).finish()
+    }
+}
+
+// Frees the memory even if dropping or taking the value panics.
+struct Dealloc<T: ?Sized> {
+    ptr: *mut T,
+    dealloc: unsafe fn(*mut T),
+}
+
+impl<T: ?Sized> Drop for Dealloc<T> {
+    fn drop(&mut self) {
+        unsafe { (self.dealloc)(self.ptr) }
+    }
+}
+
+impl<T: ?Sized> Drop for ForeignBox<T> {
+    fn drop(&mut self) {
+        let guard = Dealloc { ptr: self.ptr.as_ptr(), dealloc: self.dealloc };
+        unsafe { ptr::drop_in_place(guard.ptr) }
+    }
+}
+
+unsafe impl<T: ?Sized> RawDerefTake for ForeignBox<T> {
+    type DeallocInfo = unsafe fn(*mut T);
+
+    fn into_raw_parts(self) -> (*mut T, unsafe fn(*mut T)) {
+        ForeignBox::into_raw(self)
+    }
+
+    unsafe fn dealloc(ptr: *mut T, dealloc: unsafe fn(*mut T)) {
+        dealloc(ptr)
+    }
+}
+
+unsafe impl<T: ?Sized> Take<T> for ForeignBox<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use alloc::boxed::Box;
+    use core::cell::Cell;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    std::thread_local! {
+        static FREED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe fn free<T: ?Sized>(ptr: *mut T) {
+        FREED.with(|freed| freed.set(freed.get() + 1));
+        drop(Box::from_raw(ptr as *mut ManuallyDrop<T>));
+    }
+
+    fn foreign<T>(value: T) -> ForeignBox<T> {
+        unsafe { ForeignBox::from_raw(Box::into_raw(Box::new(value)), free::<T>) }
+    }
+
+    #[test]
+    fn test_drop() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        drop(foreign(token));
+        assert!(state.is_dropped());
+        assert_eq!(FREED.with(Cell::get), 1);
+    }
+
+    #[test]
+    fn test_take() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let token: DropToken = foreign(token).take_sized();
+        assert!(state.is_not_dropped());
+        assert_eq!(FREED.with(Cell::get), 1);
+        drop(token);
+
+        let boxed: Box<str> = "foreign".into();
+        let ptr = Box::into_raw(boxed);
+        let foreign = unsafe { ForeignBox::from_raw(ptr, free::<str>) };
+        assert_eq!(foreign.deref_take(), "foreign");
+        assert_eq!(FREED.with(Cell::get), 2);
+    }
+}
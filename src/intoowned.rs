@@ -1,12 +1,31 @@
 use super::*;
 
-use std::borrow::Borrow;
-use std::mem::ManuallyDrop;
-use std::ptr;
+use core::borrow::Borrow;
+use core::mem::ManuallyDrop;
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    boxed::Box,
+    ffi::CString,
+    string::String,
+    vec::Vec,
+};
+#[cfg(feature = "alloc")]
+use core::{ffi::CStr, ptr};
+
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
 
 /// Conversion from unsized to sized.
 ///
 /// Similar to `ToOwned`, but by taking ownership rather than duplicating.
+///
+/// # Safety
+///
+/// Implementations must return a value that takes over ownership of the contents of `this`, with
+/// the original value treated as moved-out rather than dropped.
 pub unsafe trait IntoOwned {
     /// The resulting `Sized` type after conversion.
     type Owned : Borrow<Self> + Take<Self>;
@@ -20,6 +39,18 @@ pub unsafe trait IntoOwned {
     /// used. In particular, `drop()` must not be called, and this function can only be called at
     /// most once for a given `ManuallyDrop<Self>` instance.
     unsafe fn into_owned_unchecked(this: &mut ManuallyDrop<Self>) -> Self::Owned;
+
+    /// Performs the conversion from a `Box`.
+    ///
+    /// The default implementation moves the value out of the box. Implementations should override
+    /// this if the owned form can reuse the box's allocation.
+    ///
+    /// With the `allocator_api` feature the `Box` impls are generic over the allocator, and don't
+    /// use this.
+    #[cfg(feature = "alloc")]
+    fn box_into_owned(this: Box<Self>) -> Self::Owned {
+        this.deref_take_unsized(|src| src.into_owned())
+    }
 }
 
 unsafe impl<T> IntoOwned for T {
@@ -30,6 +61,7 @@ unsafe impl<T> IntoOwned for T {
     }
 }
 
+#[cfg(feature = "alloc")]
 unsafe impl<T> IntoOwned for [T] {
     type Owned = Vec<T>;
 
@@ -43,8 +75,119 @@ unsafe impl<T> IntoOwned for [T] {
 
         r
     }
+
+    fn box_into_owned(this: Box<Self>) -> Self::Owned {
+        this.into_vec()
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl IntoOwned for str {
+    type Owned = String;
+
+    unsafe fn into_owned_unchecked(this: &mut ManuallyDrop<str>) -> Self::Owned {
+        let bytes = &mut *(this as *mut ManuallyDrop<str> as *mut ManuallyDrop<[u8]>);
+        String::from_utf8_unchecked(<[u8]>::into_owned_unchecked(bytes))
+    }
+
+    fn box_into_owned(this: Box<Self>) -> Self::Owned {
+        this.into_string()
+    }
+}
+
+// These types have no drop glue, so copying the bytes is equivalent to moving them.
+
+#[cfg(feature = "alloc")]
+unsafe impl IntoOwned for CStr {
+    type Owned = CString;
+
+    unsafe fn into_owned_unchecked(this: &mut ManuallyDrop<CStr>) -> Self::Owned {
+        CString::from(&**this)
+    }
+
+    fn box_into_owned(this: Box<Self>) -> Self::Owned {
+        this.into_c_string()
+    }
 }
 
-#[cfg(test)]
+#[cfg(feature = "std")]
+unsafe impl IntoOwned for OsStr {
+    type Owned = OsString;
+
+    unsafe fn into_owned_unchecked(this: &mut ManuallyDrop<OsStr>) -> Self::Owned {
+        this.to_os_string()
+    }
+
+    fn box_into_owned(this: Box<Self>) -> Self::Owned {
+        this.into_os_string()
+    }
+}
+
+#[cfg(feature = "std")]
+unsafe impl IntoOwned for Path {
+    type Owned = PathBuf;
+
+    unsafe fn into_owned_unchecked(this: &mut ManuallyDrop<Path>) -> Self::Owned {
+        this.to_path_buf()
+    }
+
+    fn box_into_owned(this: Box<Self>) -> Self::Owned {
+        this.into_path_buf()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        let boxed: Box<str> = "hello".into();
+        let s: String = Take::<str>::take_owned(boxed);
+        assert_eq!(s, "hello");
+
+        let boxed: Box<str> = "hello".into();
+        assert_eq!(boxed.deref_take(), "hello");
+
+        let s = Take::<str>::take_owned(String::from("world"));
+        assert_eq!(s, "world");
+
+        let len = Take::<str>::take_unsized(String::from("world"), |src| src.len());
+        assert_eq!(len, 5);
+    }
+
+    #[test]
+    #[cfg(not(feature = "allocator_api"))]
+    fn test_box_reuses_allocation() {
+        let boxed: Box<[u8]> = vec![1, 2, 3].into_boxed_slice();
+        let ptr = boxed.as_ptr();
+        let v = Take::<[u8]>::take_owned(boxed);
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v, [1, 2, 3]);
+
+        let boxed: Box<str> = "hello".into();
+        let ptr = boxed.as_ptr();
+        let s = boxed.deref_take();
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s, "hello");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_std_dsts() {
+        let boxed: Box<CStr> = CString::new("c").unwrap().into_boxed_c_str();
+        assert_eq!(boxed.deref_take(), CString::new("c").unwrap());
+        let s = Take::<CStr>::take_owned(CString::new("c").unwrap());
+        assert_eq!(s.as_bytes(), b"c");
+
+        let boxed: Box<OsStr> = OsStr::new("os").into();
+        assert_eq!(boxed.deref_take(), "os");
+        let s = Take::<OsStr>::take_owned(OsString::from("os"));
+        assert_eq!(s, "os");
+
+        let boxed: Box<Path> = Path::new("/tmp").into();
+        assert_eq!(boxed.deref_take(), Path::new("/tmp"));
+        let s = Take::<Path>::take_owned(PathBuf::from("/tmp"));
+        assert_eq!(s, Path::new("/tmp"));
+    }
 }
@@ -0,0 +1,205 @@
+use core::mem::ManuallyDrop;
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    boxed::Box,
+    rc::Rc,
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
+#[cfg(feature = "alloc")]
+use core::{mem::MaybeUninit, ptr};
+
+#[cfg(feature = "alloc")]
+use super::*;
+#[cfg(feature = "alloc")]
+use crate::dynintoowned::box_from_manually_drop;
+
+/// Conversion from unsized to sized, into a choice of owned forms.
+///
+/// `IntoOwned` has a single owned form per type, such as `Vec<T>` for `[T]`. This is implemented
+/// for the other common forms too, such as `Box<[T]>` and `Arc<[T]>`, so the form can be chosen
+/// with `Take::take_owned_as()` or `DerefTake::deref_take_as()`.
+///
+/// # Safety
+///
+/// Same as `IntoOwned`.
+pub unsafe trait IntoOwnedAs<O> {
+    /// Performs the conversion.
+    ///
+    /// # Safety
+    ///
+    /// Same as `IntoOwned::into_owned_unchecked()`.
+    unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<Self>) -> O;
+
+    /// Performs the conversion from a `Box`.
+    ///
+    /// As with `IntoOwned::box_into_owned()`, override this if the box's allocation can be
+    /// reused.
+    #[cfg(feature = "alloc")]
+    fn box_into_owned_as(this: Box<Self>) -> O {
+        this.deref_take_unsized(|src| unsafe { Self::into_owned_as_unchecked(TakeSlot::into_raw(src)) })
+    }
+}
+
+unsafe impl<T> IntoOwnedAs<T> for T {
+    unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<T>) -> T {
+        ManuallyDrop::take(this)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> IntoOwnedAs<Box<T>> for T {
+    unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<T>) -> Box<T> {
+        Box::new(ManuallyDrop::take(this))
+    }
+
+    fn box_into_owned_as(this: Box<T>) -> Box<T> {
+        this
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> IntoOwnedAs<Rc<T>> for T {
+    unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<T>) -> Rc<T> {
+        Rc::new(ManuallyDrop::take(this))
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> IntoOwnedAs<Arc<T>> for T {
+    unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<T>) -> Arc<T> {
+        Arc::new(ManuallyDrop::take(this))
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> IntoOwnedAs<Vec<T>> for [T] {
+    unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<[T]>) -> Vec<T> {
+        <[T]>::into_owned_unchecked(this)
+    }
+
+    fn box_into_owned_as(this: Box<[T]>) -> Vec<T> {
+        this.into_vec()
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> IntoOwnedAs<Box<[T]>> for [T] {
+    unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<[T]>) -> Box<[T]> {
+        box_from_manually_drop(this)
+    }
+
+    fn box_into_owned_as(this: Box<[T]>) -> Box<[T]> {
+        this
+    }
+}
+
+/// Moves the elements into a new `Rc` or `Arc` slice, with a single copy.
+#[cfg(feature = "alloc")]
+macro_rules! impl_into_owned_as_shared_slice {
+    ($rc:ident) => {
+        unsafe impl<T> IntoOwnedAs<$rc<[T]>> for [T] {
+            unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<[T]>) -> $rc<[T]> {
+                let len = this.len();
+                let mut rc: $rc<[MaybeUninit<T>]> = $rc::new_uninit_slice(len);
+                let dst = $rc::get_mut(&mut rc).unwrap();
+                ptr::copy_nonoverlapping(this.as_ptr(), dst.as_mut_ptr() as *mut T, len);
+                rc.assume_init()
+            }
+        }
+
+        unsafe impl IntoOwnedAs<$rc<str>> for str {
+            unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<str>) -> $rc<str> {
+                let bytes = &mut *(this as *mut ManuallyDrop<str> as *mut ManuallyDrop<[u8]>);
+                let bytes: $rc<[u8]> = <[u8]>::into_owned_as_unchecked(bytes);
+                // str has the same layout as [u8]
+                $rc::from_raw($rc::into_raw(bytes) as *const str)
+            }
+
+            fn box_into_owned_as(this: Box<str>) -> $rc<str> {
+                $rc::from(this)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+impl_into_owned_as_shared_slice!(Rc);
+#[cfg(feature = "alloc")]
+impl_into_owned_as_shared_slice!(Arc);
+
+#[cfg(feature = "alloc")]
+unsafe impl IntoOwnedAs<String> for str {
+    unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<str>) -> String {
+        str::into_owned_unchecked(this)
+    }
+
+    fn box_into_owned_as(this: Box<str>) -> String {
+        this.into_string()
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl IntoOwnedAs<Box<str>> for str {
+    unsafe fn into_owned_as_unchecked(this: &mut ManuallyDrop<str>) -> Box<str> {
+        box_from_manually_drop(this)
+    }
+
+    fn box_into_owned_as(this: Box<str>) -> Box<str> {
+        this
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    #[test]
+    fn test_slices() {
+        let check = DropCheck::new();
+
+        let v: Vec<DropToken> = (0 .. 3).map(|_| check.token()).collect();
+        let arc: Arc<[DropToken]> = Take::<[DropToken]>::take_owned_as(v);
+        assert_eq!(arc.len(), 3);
+        assert!(check.none_dropped());
+        drop(arc);
+        assert!(check.all_dropped());
+
+        let rc: Rc<[u8]> = vec![4u8, 5].deref_take_as();
+        assert_eq!(*rc, [4, 5]);
+    }
+
+    #[test]
+    #[cfg(not(feature = "allocator_api"))]
+    fn test_box_reused() {
+        let boxed: Box<[u8]> = Box::new([1, 2, 3]);
+        let ptr = boxed.as_ptr();
+        let boxed: Box<[u8]> = Take::<[u8]>::take_owned_as(boxed);
+        assert_eq!(boxed.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_strs() {
+        let rc: Rc<str> = Take::<str>::take_owned_as(String::from("rc"));
+        assert_eq!(&*rc, "rc");
+
+        let boxed: Box<str> = "boxed".into();
+        let arc: Arc<str> = boxed.deref_take_as();
+        assert_eq!(&*arc, "boxed");
+
+        let boxed: Box<str> = String::from("string").deref_take_as();
+        assert_eq!(&*boxed, "string");
+    }
+
+    #[test]
+    fn test_sized() {
+        let boxed: Box<String> = Take::<String>::take_owned_as(Some(String::from("some")));
+        assert_eq!(*boxed, "some");
+        let s: String = Box::new(String::from("boxed")).deref_take_as();
+        assert_eq!(s, "boxed");
+    }
+}
@@ -0,0 +1,366 @@
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ptr;
+use core::slice;
+
+use alloc::{
+    rc::Rc,
+    sync::Arc,
+    vec::Vec,
+};
+
+use super::*;
+
+/// Slice containers whose elements can be taken one by one.
+pub trait IntoTakeIter : Sized {
+    /// The type of the elements.
+    type Item;
+
+    /// What owns the memory the elements are taken from.
+    type Storage;
+
+    /// Creates an iterator that moves the elements out of the container.
+    fn take_iter(self) -> TakeIter<Self::Item, Self::Storage>;
+
+    /// Creates an iterator that moves the elements out in `Vec`s of `chunk_len` elements, the last
+    /// of which may be shorter.
+    ///
+    /// Only one chunk is moved at a time, bounding the extra memory needed to take a large
+    /// container. The container's memory is freed when the iterator is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_len` is zero.
+    fn take_chunks(self, chunk_len: usize) -> TakeChunks<Self::Item, Self::Storage> {
+        assert!(chunk_len != 0, "chunk_len must be non-zero");
+        TakeChunks { iter: self.take_iter(), chunk_len }
+    }
+}
+
+/// An iterator that moves elements out of a slice container.
+///
+/// Elements not yet taken are dropped when the iterator is dropped, after which the container's
+/// memory is deallocated.
+pub struct TakeIter<T, S> {
+    ptr: *mut T,
+    start: usize,
+    end: usize,
+
+    // Owns the memory that ptr points to, but never drops the elements themselves.
+    _storage: S,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send, S: Send> Send for TakeIter<T, S> {}
+unsafe impl<T: Sync, S: Sync> Sync for TakeIter<T, S> {}
+
+impl<T, S> TakeIter<T, S> {
+    /// Creates a new iterator over `len` elements starting at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// The elements must be initialized and owned by the iterator, and `storage` must keep the
+    /// memory they're in alive without dropping or otherwise using them.
+    pub(crate) unsafe fn new(ptr: *mut T, len: usize, storage: S) -> Self {
+        TakeIter {
+            ptr,
+            start: 0,
+            end: len,
+            _storage: storage,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the elements that haven't been taken yet.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.add(self.start), self.end - self.start) }
+    }
+
+    /// Returns the elements that haven't been taken yet as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.add(self.start), self.end - self.start) }
+    }
+}
+
+impl<T, S> Iterator for TakeIter<T, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start < self.end {
+            let r = unsafe { self.ptr.add(self.start).read() };
+            self.start += 1;
+            Some(r)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, S> DoubleEndedIterator for TakeIter<T, S> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start < self.end {
+            self.end -= 1;
+            Some(unsafe { self.ptr.add(self.end).read() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, S> ExactSizeIterator for TakeIter<T, S> {}
+impl<T, S> FusedIterator for TakeIter<T, S> {}
+
+impl<T: fmt::Debug, S> fmt::Debug for TakeIter<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("TakeIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, S> Drop for TakeIter<T, S> {
+    fn drop(&mut self) {
+        // If this panics the storage is still dropped, deallocating the memory.
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+/// An iterator that moves chunks of elements out of a slice container.
+///
+/// Created by `IntoTakeIter::take_chunks()`.
+pub struct TakeChunks<T, S> {
+    iter: TakeIter<T, S>,
+    chunk_len: usize,
+}
+
+impl<T, S> TakeChunks<T, S> {
+    /// Returns the elements that haven't been taken yet.
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+}
+
+impl<T, S> Iterator for TakeChunks<T, S> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let iter = &mut self.iter;
+        let len = self.chunk_len.min(iter.end - iter.start);
+        if len > 0 {
+            let mut chunk = Vec::with_capacity(len);
+            unsafe {
+                ptr::copy_nonoverlapping(iter.ptr.add(iter.start), chunk.as_mut_ptr(), len);
+                iter.start += len;
+                chunk.set_len(len);
+            }
+            Some(chunk)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.iter.len();
+        let len = remaining.div_ceil(self.chunk_len);
+        (len, Some(len))
+    }
+}
+
+impl<T, S> ExactSizeIterator for TakeChunks<T, S> {}
+impl<T, S> FusedIterator for TakeChunks<T, S> {}
+
+impl<T: fmt::Debug, S> fmt::Debug for TakeChunks<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TakeChunks")
+         .field("remaining", &self.as_slice())
+         .field("chunk_len", &self.chunk_len)
+         .finish()
+    }
+}
+
+/// Owns the memory of a `RawDerefTake` container, freeing it on drop without dropping the value.
+pub struct RawStorage<C: RawDerefTake> {
+    ptr: *mut C::Target,
+    info: Option<C::DeallocInfo>,
+}
+
+unsafe impl<C: RawDerefTake> Send for RawStorage<C>
+    where C: Send, C::DeallocInfo: Send, C::Target: Send
+{}
+unsafe impl<C: RawDerefTake> Sync for RawStorage<C>
+    where C: Sync, C::DeallocInfo: Sync, C::Target: Sync
+{}
+
+impl<C: RawDerefTake> Drop for RawStorage<C> {
+    fn drop(&mut self) {
+        if let Some(info) = self.info.take() {
+            unsafe { C::dealloc(self.ptr, info) }
+        }
+    }
+}
+
+impl<C: RawDerefTake> fmt::Debug for RawStorage<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RawStorage").finish_non_exhaustive()
+    }
+}
+
+/// Takes the elements out of the container's memory in place, such as for `Vec`, `Box<[T]>` and
+/// `OwnedSlice`.
+impl<T, C: RawDerefTake<Target = [T]>> IntoTakeIter for C {
+    type Item = T;
+    type Storage = RawStorage<C>;
+
+    fn take_iter(self) -> TakeIter<T, RawStorage<C>> {
+        let (ptr, info) = self.into_raw_parts();
+        unsafe {
+            let len = ptr.len();
+            TakeIter::new(ptr as *mut T, len, RawStorage { ptr, info: Some(info) })
+        }
+    }
+}
+
+/// Moves the elements if the `Rc` is unique, and clones them otherwise.
+impl<T: Clone> IntoTakeIter for Rc<[T]> {
+    type Item = T;
+    type Storage = Rc<[ManuallyDrop<T>]>;
+
+    fn take_iter(mut self) -> TakeIter<T, Rc<[ManuallyDrop<T>]>> {
+        let mut storage: Rc<[ManuallyDrop<T>]> = if Rc::get_mut(&mut self).is_some() {
+            unsafe { Rc::from_raw(Rc::into_raw(self) as *const [ManuallyDrop<T>]) }
+        } else {
+            self.iter().cloned().map(ManuallyDrop::new).collect()
+        };
+
+        let unique = Rc::get_mut(&mut storage).expect("unique");
+        let (ptr, len) = (unique.as_mut_ptr() as *mut T, unique.len());
+        unsafe { TakeIter::new(ptr, len, storage) }
+    }
+}
+
+/// Moves the elements if the `Arc` is unique, and clones them otherwise.
+impl<T: Clone> IntoTakeIter for Arc<[T]> {
+    type Item = T;
+    type Storage = Arc<[ManuallyDrop<T>]>;
+
+    fn take_iter(mut self) -> TakeIter<T, Arc<[ManuallyDrop<T>]>> {
+        let mut storage: Arc<[ManuallyDrop<T>]> = if Arc::get_mut(&mut self).is_some() {
+            unsafe { Arc::from_raw(Arc::into_raw(self) as *const [ManuallyDrop<T>]) }
+        } else {
+            self.iter().cloned().map(ManuallyDrop::new).collect()
+        };
+
+        let unique = Arc::get_mut(&mut storage).expect("unique");
+        let (ptr, len) = (unique.as_mut_ptr() as *mut T, unique.len());
+        unsafe { TakeIter::new(ptr, len, storage) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_vec() {
+        let check = DropCheck::new();
+
+        let tokens: Vec<_> = (0 .. 10).map(|_| check.pair()).collect();
+        let (tokens, states): (Vec<_>, Vec<_>) = tokens.into_iter().unzip();
+
+        let mut iter = tokens.take_iter();
+        assert_eq!(iter.len(), 10);
+        let first = iter.next().unwrap();
+        let last = iter.next_back().unwrap();
+        assert_eq!(iter.len(), 8);
+        assert!(check.none_dropped());
+
+        drop(iter);
+        assert!(states[0].is_not_dropped());
+        assert!(states[9].is_not_dropped());
+        assert!(states[1 .. 9].iter().all(|s| s.is_dropped()));
+
+        drop((first, last));
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_chunks() {
+        let check = DropCheck::new();
+
+        let (tokens, states): (Vec<_>, Vec<_>) = (0 .. 7).map(|_| check.pair()).unzip();
+        let mut chunks = tokens.take_chunks(3);
+        assert_eq!(chunks.len(), 3);
+        let first = chunks.next().unwrap();
+        assert_eq!(first.len(), 3);
+        assert_eq!(chunks.as_slice().len(), 4);
+        assert!(check.none_dropped());
+        drop(chunks);
+        assert!(states[.. 3].iter().all(|s| s.is_not_dropped()));
+        assert!(states[3 ..].iter().all(|s| s.is_dropped()));
+        drop(first);
+        assert!(check.all_dropped());
+
+        let boxed: Box<[u8]> = (0 .. 5).collect();
+        let chunks: Vec<Vec<u8>> = boxed.take_chunks(2).collect();
+        assert_eq!(chunks, [vec![0, 1], vec![2, 3], vec![4]]);
+    }
+
+    #[test]
+    fn test_box() {
+        let boxed: Box<[String]> = vec![String::from("a"), String::from("b")].into_boxed_slice();
+        let v: Vec<String> = boxed.take_iter().rev().collect();
+        assert_eq!(v, ["b", "a"]);
+    }
+
+    #[test]
+    fn test_raw_deref_take() {
+        let check = DropCheck::new();
+
+        let (tokens, states): (Vec<_>, Vec<_>) = (0 .. 3).map(|_| check.pair()).unzip();
+        let owned = OwnedSlice::from(tokens);
+        let mut iter = owned.take_iter();
+        let first = iter.next().unwrap();
+        drop(iter);
+        assert!(states[0].is_not_dropped());
+        assert!(states[1 ..].iter().all(|s| s.is_dropped()));
+        drop(first);
+        assert!(check.all_dropped());
+
+        let mut buf: Box<[core::mem::MaybeUninit<String>]> = Box::new_uninit_slice(4);
+        buf[0].write(String::from("a"));
+        buf[1].write(String::from("b"));
+        let filled = unsafe { crate::uninit::InitPrefix::new(buf, 2) };
+        let v: Vec<String> = filled.take_iter().rev().collect();
+        assert_eq!(v, ["b", "a"]);
+    }
+
+    #[test]
+    fn test_rc() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let rc: Rc<[_]> = Rc::from(vec![token]);
+        let token = rc.take_iter().next().unwrap();
+        assert!(state.is_not_dropped());
+        drop(token);
+        assert!(state.is_dropped());
+
+        let rc: Rc<[String]> = Rc::from(vec![String::from("a"), String::from("b")]);
+        let rc2 = Rc::clone(&rc);
+        let v: Vec<String> = rc.take_iter().collect();
+        assert_eq!(v, ["a", "b"]);
+        assert_eq!(&*rc2, ["a", "b"]);
+
+        let arc: Arc<[String]> = Arc::from(vec![String::from("a"), String::from("b")]);
+        let mut iter = arc.take_iter();
+        assert_eq!(iter.as_slice(), ["a", "b"]);
+        assert_eq!(iter.next().unwrap(), "a");
+    }
+}
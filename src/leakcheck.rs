@@ -0,0 +1,224 @@
+//! Runtime checks for misuse of `TakeSlot`, enabled by the `debug-leakcheck` feature.
+//!
+//! In debug builds the take calls of this crate's containers record where the value being taken
+//! lives, and whether its slot has moved it out. Creating a second slot for a value that already
+//! has a live slot panics, as does `assert_untaken()` on a value that a slot has moved out. The
+//! record is forgotten when the take call returns, so memory that's later reused for another value
+//! starts afresh. In release builds the checks compile to nothing.
+
+#[cfg(debug_assertions)]
+use std::{cell::RefCell, collections::HashMap};
+
+#[cfg(debug_assertions)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Live,
+    Taken,
+}
+
+#[cfg(debug_assertions)]
+type Key = (usize, &'static str);
+
+#[cfg(debug_assertions)]
+std::thread_local! {
+    static SLOTS: RefCell<HashMap<Key, State>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the key of the value, or `None` for zero-sized values as they don't occupy memory.
+///
+/// Only called with pointers from `TakeSlot`s and references, which are valid.
+#[cfg(debug_assertions)]
+fn key<T: ?Sized>(ptr: *const T) -> Option<Key> {
+    if core::mem::size_of_val(unsafe { &*ptr }) == 0 {
+        None
+    } else {
+        Some((ptr as *const u8 as usize, core::any::type_name::<T>()))
+    }
+}
+
+/// Applies `f` to the state of `key`.
+///
+/// Does nothing once the thread-local is gone, as values can still be taken by the destructors of
+/// other thread-locals.
+#[cfg(debug_assertions)]
+fn update(key: Key, f: impl FnOnce(Option<State>) -> Option<State>) {
+    let _ = SLOTS.try_with(|slots| {
+        let mut slots = slots.borrow_mut();
+        let old = slots.get(&key).copied();
+        match f(old) {
+            Some(state) => slots.insert(key, state),
+            None => slots.remove(&key),
+        };
+    });
+}
+
+#[cfg(debug_assertions)]
+fn double_take<T: ?Sized>(name: &str, ptr: *const T) -> ! {
+    panic!("double take: the {} at {:p} already has a live TakeSlot", name, ptr as *const u8)
+}
+
+/// Records the slot of a take call, until the guard is dropped at the end of the call.
+pub(crate) struct Scope {
+    #[cfg(debug_assertions)]
+    key: Option<Key>,
+}
+
+impl Scope {
+    /// Starts tracking a new slot for the value.
+    #[inline(always)]
+    pub(crate) fn enter<T: ?Sized>(_ptr: *const T) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let key = key(_ptr);
+            if let Some(key) = key {
+                update(key, |old| {
+                    if old == Some(State::Live) {
+                        double_take(key.1, _ptr);
+                    }
+                    Some(State::Live)
+                });
+            }
+            Scope { key }
+        }
+
+        #[cfg(not(debug_assertions))]
+        Scope {}
+    }
+}
+
+impl Drop for Scope {
+    #[inline(always)]
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if let Some(key) = self.key {
+            update(key, |_| None);
+        }
+    }
+}
+
+/// A slot was created for the value outside of a take call of this crate.
+///
+/// Such slots aren't tracked, as nothing would forget them. But they still mustn't alias a tracked
+/// live slot.
+#[inline(always)]
+pub(crate) fn slot_created<T: ?Sized>(_ptr: *const T) {
+    #[cfg(debug_assertions)]
+    if let Some(key) = key(_ptr) {
+        update(key, |old| {
+            if old == Some(State::Live) {
+                double_take(key.1, _ptr);
+            }
+            old
+        })
+    }
+}
+
+/// The slot moved the value out.
+#[inline(always)]
+pub(crate) fn slot_taken<T: ?Sized>(_ptr: *const T) {
+    #[cfg(debug_assertions)]
+    if let Some(key) = key(_ptr) {
+        update(key, |old| old.map(|_| State::Taken))
+    }
+}
+
+/// The slot was dropped without moving the value out, or gave up the value with `into_raw()`.
+#[inline(always)]
+pub(crate) fn slot_released<T: ?Sized>(_ptr: *const T) {
+    #[cfg(debug_assertions)]
+    if let Some(key) = key(_ptr) {
+        update(key, |_| None)
+    }
+}
+
+/// Panics if a `TakeSlot` moved `value` out.
+///
+/// `Take` implementations can call this before dropping or otherwise using a value they may have
+/// handed to a slot, e.g. with a reference to the contents of a `ManuallyDrop`. Only slots created
+/// by this crate's take calls are tracked, and only until the call returns.
+#[inline(always)]
+pub fn assert_untaken<T: ?Sized>(_value: &T) {
+    #[cfg(debug_assertions)]
+    if let Some(key) = key(_value) {
+        update(key, |old| {
+            if old == Some(State::Taken) {
+                panic!("use after take: the {} at {:p} was moved out by a TakeSlot",
+                       key.1, _value as *const T as *const u8);
+            }
+            old
+        })
+    }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod test {
+    use super::*;
+
+    use core::mem::{self, ManuallyDrop};
+
+    use crate::{Take, TakeSlot};
+
+    fn tracked() -> usize {
+        SLOTS.with(|slots| slots.borrow().len())
+    }
+
+    #[test]
+    #[should_panic(expected = "double take")]
+    fn test_double_take() {
+        Take::<String>::take_unsized(String::new(), |mut first| {
+            let ptr = TakeSlot::as_mut_ptr(&mut first) as *mut ManuallyDrop<String>;
+            let _second = unsafe { TakeSlot::new(&mut *ptr) };
+        })
+    }
+
+    // Using a value after it's taken is UB under Stacked Borrows, which Miri reports before the
+    // check gets to panic.
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    #[should_panic(expected = "use after take")]
+    fn test_use_after_take() {
+        Take::<String>::take_unsized(String::from("taken"), |slot| {
+            let ptr = TakeSlot::as_ptr(&slot);
+            let s = slot.read();
+            assert_eq!(s, "taken");
+            assert_untaken(unsafe { &*ptr });
+        })
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    #[should_panic(expected = "use after take")]
+    fn test_drop_after_take() {
+        use core::ptr;
+
+        use crate::DerefTake;
+
+        let v = vec![String::from("a"), String::from("b")];
+        v.deref_take_unsized_with_len(|src, _| {
+            let src: TakeSlot<'_, [String]> = unsafe { ptr::read(src) };
+            src.into_owned()
+        });
+    }
+
+    #[test]
+    fn test_reuse() {
+        for i in 0 .. 3 {
+            let s = Take::<String>::take_unsized("a".repeat(i), |slot| slot.read());
+            assert_eq!(s.len(), i);
+        }
+        for _ in 0 .. 3 {
+            Take::<String>::take_unsized(String::new(), |slot| mem::forget(slot));
+            Take::<String>::take_unsized(String::new(), |slot| drop(slot));
+        }
+        assert_eq!(tracked(), 0);
+    }
+
+    #[test]
+    fn test_untracked() {
+        let mut value = ManuallyDrop::new(String::from("untracked"));
+        let s = unsafe { TakeSlot::new(&mut value) }.read();
+        assert_eq!(s, "untracked");
+        assert_untaken(&*value);
+        assert_eq!(tracked(), 0);
+    }
+}
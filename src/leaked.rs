@@ -0,0 +1,163 @@
+use core::fmt;
+use core::ops;
+use core::ptr::NonNull;
+
+use alloc::boxed::Box;
+
+use super::*;
+
+/// Takes back ownership of a value leaked with `Box::leak()`.
+///
+/// The allocation is freed, or reused by the owned form where possible.
+///
+/// Takes a raw pointer, e.g. `Box::leak(b) as *mut T`, rather than the reference itself: a
+/// reference argument would have to stay valid for the whole call, while the memory is freed
+/// during it.
+///
+/// # Safety
+///
+/// `leaked` must have come from `Box::leak()` or `Box::into_raw()` on a `Box` using the global
+/// allocator, and no references derived from it may be used afterwards.
+pub unsafe fn take_leaked<T: ?Sized + IntoOwned>(leaked: *mut T) -> T::Owned {
+    Box::from_raw(leaked).deref_take()
+}
+
+/// A leaked `Box`: dropping it leaks the value, but ownership can still be taken back.
+///
+/// This is for values that are meant to live for the rest of the program, such as configuration
+/// set up at startup, that should still be reclaimed for a clean shutdown. Unlike `take_leaked()`
+/// taking from a `Leaked` is safe, as it can only have come from a `Box`.
+pub struct Leaked<T: ?Sized> {
+    ptr: NonNull<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for Leaked<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for Leaked<T> {}
+
+impl<T: ?Sized> Leaked<T> {
+    /// Leaks a `Box`.
+    pub fn new(boxed: Box<T>) -> Self {
+        Leaked { ptr: NonNull::from(Box::leak(boxed)) }
+    }
+
+    /// Creates a `Leaked` from a reference returned by `Box::leak()`.
+    ///
+    /// # Safety
+    ///
+    /// `leaked` must have come from `Box::leak()` on a `Box` using the global allocator, and no
+    /// other references derived from it may be used afterwards.
+    pub unsafe fn from_static(leaked: &'static mut T) -> Self
+        where T: 'static
+    {
+        Leaked { ptr: NonNull::from(leaked) }
+    }
+
+    /// Gives up the ability to take the value back, returning a `'static` reference to it.
+    pub fn into_static(this: Self) -> &'static mut T
+        where T: 'static
+    {
+        unsafe { &mut *this.ptr.as_ptr() }
+    }
+}
+
+impl<T: ?Sized> ops::Deref for Leaked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: ?Sized> ops::DerefMut for Leaked<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Leaked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Leaked").field(&&**self).finish()
+    }
+}
+
+unsafe impl<T: ?Sized> RawDerefTake for Leaked<T> {
+    type DeallocInfo = <Box<T> as RawDerefTake>::DeallocInfo;
+
+    fn into_raw_parts(self) -> (*mut T, Self::DeallocInfo) {
+        unsafe { Box::from_raw(self.ptr.as_ptr()) }.into_raw_parts()
+    }
+
+    unsafe fn dealloc(ptr: *mut T, info: Self::DeallocInfo) {
+        <Box<T> as RawDerefTake>::dealloc(ptr, info)
+    }
+
+    fn raw_deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        unsafe { Box::from_raw(self.ptr.as_ptr()) }.deref_take()
+    }
+}
+
+unsafe impl<T: ?Sized> Take<T> for Leaked<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+crate::takeorclone::impl_take_or_clone!([T: ?Sized] Leaked<T> => T);
+crate::fallible::impl_try_take!([T: ?Sized] Leaked<T> => T);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use alloc::{rc::Rc, string::String};
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_take_leaked() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let leaked = Box::leak(Box::new(token));
+        let token = unsafe { take_leaked(leaked as *mut _) };
+        assert!(state.is_not_dropped());
+        drop(token);
+        assert!(state.is_dropped());
+
+        let leaked: &'static mut str = Box::leak("leaked".into());
+        let ptr = leaked.as_ptr();
+        let s: String = unsafe { take_leaked(leaked as *mut str) };
+        // With allocator_api, Box<T, A> doesn't use box_into_owned() and reallocates.
+        #[cfg(not(feature = "allocator_api"))]
+        assert_eq!(s.as_ptr(), ptr);
+        #[cfg(feature = "allocator_api")]
+        let _ = ptr;
+        assert_eq!(s, "leaked");
+    }
+
+    #[test]
+    fn test_leaked() {
+        let check = DropCheck::new();
+
+        let rc = Rc::new(());
+        let ptr: *mut Rc<()> = {
+            let mut leaked = Leaked::new(Box::new(Rc::clone(&rc)));
+            &mut *leaked
+        };
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        // Reclaim the box, so Miri's leak check passes.
+        drop(unsafe { Box::from_raw(ptr) });
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let mut leaked = Leaked::new(Box::new(check.token()));
+        let _ = &mut *leaked;
+        drop(leaked.deref_take());
+        let leaked: Leaked<[u8]> = Leaked::new(Box::new([1, 2, 3]));
+        assert_eq!(Take::<[u8]>::take_owned(leaked), [1, 2, 3]);
+    }
+}
@@ -1,14 +1,156 @@
 //! Traits for taking ownership of values.
+//!
+//! The `std` feature is enabled by default. Without it the crate is `no_std`; the `alloc` feature
+//! then enables the impls for `Box`, `Vec`, `Rc` and friends.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 mod dereftake;
 pub use self::dereftake::*;
 
+mod dereftakeext;
+pub use self::dereftakeext::DerefTakeExt;
+
+#[cfg(feature = "alloc")]
+mod trydereftake;
+#[cfg(feature = "alloc")]
+pub use self::trydereftake::TryDerefTake;
+
+pub mod raw;
+
+#[cfg(feature = "const_take")]
+pub mod consttake;
+
+#[cfg(feature = "alloc")]
+pub mod uninit;
+
+mod rawdereftake;
+pub use self::rawdereftake::RawDerefTake;
+
+#[cfg(feature = "debug-leakcheck")]
+pub mod leakcheck;
+
+mod takeslot;
+pub use self::takeslot::TakeSlot;
+
 mod take;
-pub use self::take::Take;
+pub use self::take::{Take, take_or_restore, take_or_restore_with};
+
+mod takefrom;
+pub use self::takefrom::{TakeFrom, TakeInto};
+
+mod fallible;
+pub use self::fallible::{TryIntoOwned, TryTake};
+
+mod takecow;
+pub use self::takecow::TakeCow;
+
+mod takereplace;
+pub use self::takereplace::TakeReplace;
+
+#[cfg(feature = "alloc")]
+mod takemut;
+#[cfg(feature = "alloc")]
+pub use self::takemut::{TakeMut, take_mut_slice};
+
+mod cloned;
+pub use self::cloned::Cloned;
+#[cfg(feature = "alloc")]
+pub use self::cloned::ClonedUnsized;
+
+#[cfg(feature = "alloc")]
+mod clonetake;
+#[cfg(feature = "alloc")]
+pub use self::clonetake::CloneTake;
+
+#[cfg(feature = "alloc")]
+mod takeorclone;
+#[cfg(feature = "alloc")]
+pub use self::takeorclone::TakeOrClone;
+
+#[cfg(feature = "alloc")]
+mod iter;
+#[cfg(feature = "alloc")]
+pub use self::iter::{IntoTakeIter, RawStorage, TakeChunks, TakeIter};
+
+#[cfg(feature = "alloc")]
+mod leaked;
+#[cfg(feature = "alloc")]
+pub use self::leaked::{Leaked, take_leaked};
+
+#[cfg(feature = "alloc")]
+mod ownedslice;
+#[cfg(feature = "alloc")]
+pub use self::ownedslice::OwnedSlice;
+
+#[cfg(feature = "alloc")]
+mod vectake;
+#[cfg(feature = "alloc")]
+pub use self::vectake::{TryTakeAllError, VecTakeExt};
+
+#[cfg(feature = "alloc")]
+mod anytake;
+#[cfg(feature = "alloc")]
+pub use self::anytake::AnyTakeExt;
+
+#[cfg(feature = "alloc")]
+mod dyntake;
+#[cfg(feature = "alloc")]
+pub use self::dyntake::DynTake;
+
+#[cfg(feature = "alloc")]
+mod dynintoowned;
+#[cfg(feature = "alloc")]
+pub use self::dynintoowned::DynIntoOwned;
+
+mod foreign;
+pub use self::foreign::ForeignBox;
+
+mod partial;
+pub use self::partial::TakePartial;
+
+mod takeonce;
+pub use self::takeonce::{AtomicTakeOnce, TakeOnce};
+
+mod tuple;
+pub use self::tuple::TakeTuple;
 
 mod intoowned;
 pub use self::intoowned::IntoOwned;
 
+mod owned;
+pub use self::owned::Owned;
+
+mod intoownedas;
+pub use self::intoownedas::IntoOwnedAs;
+
+mod ext;
+#[cfg(feature = "bumpalo")]
+pub use self::ext::BumpTakeExt;
+#[cfg(feature = "rayon")]
+pub use self::ext::ParTakeExt;
+
+#[cfg(feature = "derive")]
+pub use owned_derive::{DerefTake, IntoOwned, Take};
+
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+pub mod __private {
+    pub use alloc::alloc::{alloc, handle_alloc_error, Layout};
+    pub use alloc::boxed::Box;
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
+    pub use core::mem::ManuallyDrop;
+
+    pub use crate::dynintoowned::box_from_manually_drop;
+    pub use crate::__impl_take_or_clone as impl_take_or_clone;
+    pub use crate::__impl_try_take as impl_try_take;
+}
+
 #[cfg(test)]
 mod tests {
 }
@@ -0,0 +1,175 @@
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops;
+
+use super::*;
+
+/// The owned form of `B`, dereferencing to `B`.
+///
+/// Like a `Cow` that's always owned, this lets the owned form of a type be stored generically,
+/// such as `Owned<str>` holding a `String`, without naming `B::Owned`.
+pub struct Owned<B: ?Sized + IntoOwned> {
+    inner: B::Owned,
+}
+
+impl<B: ?Sized + IntoOwned> Owned<B> {
+    /// Takes ownership of the value in `src`.
+    pub fn new(src: impl Take<B>) -> Self {
+        Owned { inner: src.take_owned() }
+    }
+
+    /// Wraps an already owned value.
+    pub fn from_owned(inner: B::Owned) -> Self {
+        Owned { inner }
+    }
+
+    /// Returns the owned value.
+    pub fn into_inner(self) -> B::Owned {
+        self.inner
+    }
+}
+
+impl<B: ?Sized + IntoOwned> ops::Deref for Owned<B> {
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        self.inner.borrow()
+    }
+}
+
+impl<B: ?Sized + IntoOwned> Borrow<B> for Owned<B> {
+    fn borrow(&self) -> &B {
+        self
+    }
+}
+
+impl<B: ?Sized + IntoOwned> AsRef<B> for Owned<B> {
+    fn as_ref(&self) -> &B {
+        self
+    }
+}
+
+unsafe impl<B: ?Sized + IntoOwned> DerefTake for Owned<B> {
+    fn deref_take(self) -> B::Owned {
+        self.inner
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, B>) -> R
+    {
+        self.inner.take_unsized(f)
+    }
+}
+
+crate::dereftake::impl_deref_take_for_pin!([B: ?Sized + IntoOwned] Owned<B>);
+
+unsafe impl<B: ?Sized + IntoOwned> Take<B> for Owned<B> {
+    fn take_owned(self) -> B::Owned {
+        self.inner
+    }
+
+    fn take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, B>) -> R
+    {
+        self.inner.take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+crate::takeorclone::impl_take_or_clone!([B: ?Sized + IntoOwned] Owned<B> => B);
+crate::fallible::impl_try_take!([B: ?Sized + IntoOwned] Owned<B> => B);
+
+impl<B: ?Sized + IntoOwned> Clone for Owned<B>
+    where B::Owned: Clone
+{
+    fn clone(&self) -> Self {
+        Owned { inner: self.inner.clone() }
+    }
+}
+
+impl<B: ?Sized + IntoOwned + fmt::Debug> fmt::Debug for Owned<B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<B: ?Sized + IntoOwned + fmt::Display> fmt::Display for Owned<B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<B: ?Sized + IntoOwned + PartialEq> PartialEq for Owned<B> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<B: ?Sized + IntoOwned + Eq> Eq for Owned<B> {}
+
+impl<B: ?Sized + IntoOwned + PartialOrd> PartialOrd for Owned<B> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<B: ?Sized + IntoOwned + Ord> Ord for Owned<B> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<B: ?Sized + IntoOwned + Hash> Hash for Owned<B> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use alloc::{boxed::Box, string::String, vec::Vec};
+
+    use dropcheck::{DropCheck, DropToken};
+
+    struct Message {
+        text: Owned<str>,
+        bytes: Owned<[u8]>,
+    }
+
+    #[test]
+    fn test_owned() {
+        let boxed: Box<str> = "boxed".into();
+        let msg = Message {
+            text: Owned::new(boxed),
+            bytes: Owned::new([1u8, 2, 3]),
+        };
+        assert_eq!(&*msg.text, "boxed");
+        assert_eq!(msg.text.len(), 5);
+        assert_eq!(&*msg.bytes, [1, 2, 3]);
+
+        let s: String = msg.text.deref_take();
+        assert_eq!(s, "boxed");
+        let v: Vec<u8> = Take::<[u8]>::take_owned(msg.bytes);
+        assert_eq!(v, [1, 2, 3]);
+
+        let a: Owned<str> = Owned::from_owned(String::from("a"));
+        let ab = Owned::<str>::from_owned(a.clone().into_inner() + "b");
+        assert!(a < ab);
+    }
+
+    #[test]
+    fn test_sized() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let owned = Owned::<DropToken>::new(Box::new(token));
+        let token = owned.into_inner();
+        assert!(state.is_not_dropped());
+        drop(token);
+        assert!(state.is_dropped());
+    }
+}
@@ -0,0 +1,155 @@
+use core::fmt;
+use core::ops;
+
+use alloc::{boxed::Box, vec::Vec};
+
+use super::*;
+use crate::dynintoowned::box_from_manually_drop;
+
+/// An owned slice of exactly the right size: a pointer and a length, with no spare capacity.
+///
+/// The owned form of `[T]` is `Vec<T>`, which carries a capacity and can end up holding onto
+/// more memory than it needs. `OwnedSlice::take_from()` takes a slice into an exact-size
+/// allocation instead.
+pub struct OwnedSlice<T>(Box<[T]>);
+
+impl<T> OwnedSlice<T> {
+    /// Takes ownership of a slice, moving the elements into an exact-size allocation.
+    ///
+    /// Use `From` for a `Vec<T>` or `Box<[T]>`, which may reuse the allocation.
+    pub fn take_from<C: Take<[T]>>(src: C) -> Self {
+        src.take_unsized(|src| OwnedSlice(unsafe { box_from_manually_drop(TakeSlot::into_raw(src)) }))
+    }
+
+    /// Converts into a boxed slice, without reallocating.
+    pub fn into_boxed_slice(self) -> Box<[T]> {
+        self.0
+    }
+
+    /// Converts into a `Vec`, without reallocating.
+    pub fn into_vec(self) -> Vec<T> {
+        self.0.into_vec()
+    }
+}
+
+impl<T> From<Box<[T]>> for OwnedSlice<T> {
+    fn from(boxed: Box<[T]>) -> Self {
+        OwnedSlice(boxed)
+    }
+}
+
+/// Reallocates if the `Vec` has spare capacity.
+impl<T> From<Vec<T>> for OwnedSlice<T> {
+    fn from(v: Vec<T>) -> Self {
+        OwnedSlice(v.into_boxed_slice())
+    }
+}
+
+impl<T> From<OwnedSlice<T>> for Box<[T]> {
+    fn from(slice: OwnedSlice<T>) -> Self {
+        slice.into_boxed_slice()
+    }
+}
+
+impl<T> From<OwnedSlice<T>> for Vec<T> {
+    fn from(slice: OwnedSlice<T>) -> Self {
+        slice.into_vec()
+    }
+}
+
+impl<T> ops::Deref for OwnedSlice<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for OwnedSlice<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+}
+
+impl<T: Clone> Clone for OwnedSlice<T> {
+    fn clone(&self) -> Self {
+        OwnedSlice(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OwnedSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+unsafe impl<T> RawDerefTake for OwnedSlice<T> {
+    type DeallocInfo = <Box<[T]> as RawDerefTake>::DeallocInfo;
+
+    fn into_raw_parts(self) -> (*mut [T], Self::DeallocInfo) {
+        self.0.into_raw_parts()
+    }
+
+    unsafe fn dealloc(ptr: *mut [T], info: Self::DeallocInfo) {
+        <Box<[T]> as RawDerefTake>::dealloc(ptr, info)
+    }
+
+    fn raw_deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        Self::Target::box_into_owned(self.0)
+    }
+}
+
+unsafe impl<T> Take<[T]> for OwnedSlice<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+crate::takeorclone::impl_take_or_clone!([T] OwnedSlice<T> => [T]);
+crate::fallible::impl_try_take!([T] OwnedSlice<T> => [T]);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::VecDeque;
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_take_from() {
+        let check = DropCheck::new();
+
+        let mut v = Vec::with_capacity(10);
+        v.push(check.token());
+        v.push(check.token());
+        let slice = OwnedSlice::take_from(v);
+        assert_eq!(slice.len(), 2);
+        assert!(check.none_dropped());
+
+        let v: Vec<_> = slice.into();
+        assert_eq!(v.capacity(), 2);
+        drop(v);
+        assert!(check.all_dropped());
+
+        let deque: VecDeque<u8> = (0 .. 3).collect();
+        assert_eq!(*OwnedSlice::take_from(deque), [0, 1, 2]);
+        assert!(OwnedSlice::<String>::take_from(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_take() {
+        let boxed: Box<[u8]> = Box::new([1, 2, 3]);
+        let ptr = boxed.as_ptr();
+        let slice = OwnedSlice::from(boxed);
+        let v = slice.deref_take();
+        assert_eq!(v.as_ptr(), ptr);
+
+        let slice = OwnedSlice::from(vec![String::from("a")]);
+        assert_eq!(Take::<[String]>::take_owned(slice), ["a"]);
+    }
+}
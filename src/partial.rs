@@ -0,0 +1,158 @@
+use core::mem::ManuallyDrop;
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    boxed::Box,
+    rc::Rc,
+    sync::Arc,
+};
+
+use super::*;
+
+/// Containers that fields can be moved out of, with the rest of the value dropped.
+///
+/// Usually used via `take_field!`.
+pub trait TakePartial : Sized {
+    /// What the closure is given to move fields out of.
+    ///
+    /// This is a `Box<T>` for boxes, so fields are moved out in place, and the value itself for
+    /// other containers.
+    type Partial;
+
+    /// Takes ownership of the value, giving it to `f` so fields can be moved out.
+    ///
+    /// Whatever `f` doesn't move out is dropped when it returns.
+    fn take_partial<F, R>(self, f: F) -> R
+        where F: FnOnce(Self::Partial) -> R;
+}
+
+/// Moves a field out of a container, dropping the other fields.
+///
+/// Works with any `TakePartial` container. Nested fields and tuple indexes can be used too.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use owned::take_field;
+///
+/// struct Message {
+///     header: String,
+///     body: Vec<u8>,
+/// }
+///
+/// let msg = Box::new(Message { header: String::from("hi"), body: vec![0; 4096] });
+/// let body: Vec<u8> = take_field!(msg, .body);
+/// assert_eq!(body.len(), 4096);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_field {
+    ($src:expr, $($field:tt)+) => {
+        $crate::TakePartial::take_partial($src, |partial| partial $($field)+)
+    };
+}
+
+impl<T> TakePartial for ManuallyDrop<T> {
+    type Partial = T;
+
+    fn take_partial<F, R>(self, f: F) -> R
+        where F: FnOnce(T) -> R
+    {
+        f(ManuallyDrop::into_inner(self))
+    }
+}
+
+/// Fields are moved out in place, without moving the whole value out of the box.
+#[cfg(feature = "alloc")]
+impl<T> TakePartial for Box<T> {
+    type Partial = Self;
+
+    fn take_partial<F, R>(self, f: F) -> R
+        where F: FnOnce(Self) -> R
+    {
+        f(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone> TakePartial for Rc<T> {
+    type Partial = T;
+
+    fn take_partial<F, R>(self, f: F) -> R
+        where F: FnOnce(T) -> R
+    {
+        f(self.deref_take_unsized(|src| src.read()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone> TakePartial for Arc<T> {
+    type Partial = T;
+
+    fn take_partial<F, R>(self, f: F) -> R
+        where F: FnOnce(T) -> R
+    {
+        f(self.deref_take_unsized(|src| src.read()))
+    }
+}
+
+impl<T> TakePartial for ForeignBox<T> {
+    type Partial = T;
+
+    fn take_partial<F, R>(self, f: F) -> R
+        where F: FnOnce(T) -> R
+    {
+        f(self.deref_take_unsized(|src| src.read()))
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    #[derive(Clone)]
+    struct Pair {
+        a: DropToken,
+        b: (DropToken, DropToken),
+    }
+
+    #[test]
+    fn test_box() {
+        let check = DropCheck::new();
+
+        let (a, a_state) = check.pair();
+        let (b0, b0_state) = check.pair();
+        let (b1, b1_state) = check.pair();
+        let boxed = Box::new(Pair { a, b: (b0, b1) });
+
+        let b1 = take_field!(boxed, .b.1);
+        assert!(a_state.is_dropped());
+        assert!(b0_state.is_dropped());
+        assert!(b1_state.is_not_dropped());
+        drop(b1);
+    }
+
+    #[test]
+    fn test_rc() {
+        let check = DropCheck::new();
+
+        let (a, a_state) = check.pair();
+        let (b0, b0_state) = check.pair();
+        let rc = Rc::new(Pair { a, b: (b0, check.token()) });
+
+        let a = take_field!(rc, .a);
+        assert!(a_state.is_not_dropped());
+        assert!(b0_state.is_dropped());
+        drop(a);
+
+        // Shared, so the fields are cloned.
+        let rc = Rc::new(Pair { a: check.token(), b: (check.token(), check.token()) });
+        let rc2 = Rc::clone(&rc);
+        let _b = take_field!(rc, .b);
+        assert_eq!(Rc::strong_count(&rc2), 1);
+
+        let md = ManuallyDrop::new((String::from("x"), String::from("y")));
+        assert_eq!(take_field!(md, .1), "y");
+    }
+}
@@ -0,0 +1,93 @@
+//! Helpers for taking values out of raw pointers.
+//!
+//! These are meant for implementing `DerefTake` and `Take` for custom smart pointers: the pointer
+//! is turned into a raw pointer, the value is taken with one of these functions, and then the
+//! pointer's memory is freed without dropping the value.
+//!
+//! ```
+//! use core::ptr::NonNull;
+//!
+//! # #[cfg(feature = "alloc")] {
+//! let ptr = NonNull::from(Box::leak(Box::new(String::from("raw"))));
+//! let taken: String = unsafe { owned::raw::take_from_nonnull(ptr) };
+//! assert_eq!(taken, "raw");
+//!
+//! // The value was moved out, so only the memory is left to free.
+//! drop(unsafe { Box::from_raw(ptr.as_ptr() as *mut core::mem::ManuallyDrop<String>) });
+//! # }
+//! ```
+
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+use super::*;
+
+/// Returns a pointer to `addr`, with the metadata, if any, of `ptr`.
+///
+/// The result has to carry `addr`'s provenance, not `ptr`'s, so offsetting `ptr` won't do.
+/// Instead the data pointer is overwritten in place, which assumes it's the first word of a fat
+/// pointer, as `set_ptr_value()` did before `ptr::metadata` existed. Switch to
+/// `set_ptr_value()` once it's stable.
+#[cfg(any(feature = "alloc", feature = "bumpalo"))]
+pub(crate) fn with_addr_of<T: ?Sized>(ptr: *mut T, addr: *mut u8) -> *mut T {
+    let mut ptr = ptr;
+    unsafe { (&mut ptr as *mut *mut T).cast::<*mut u8>().write(addr) };
+    ptr
+}
+
+/// Takes the value pointed to with the aid of a closure, as per `Take::take_unsized()`.
+///
+/// The memory itself is left alone. If the closure panics the value is leaked, so the caller
+/// should still free the memory, e.g. with a guard.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads and writes, and the value must be owned by the caller: once this
+/// is called the value must not be dropped or otherwise used again.
+pub unsafe fn take_unsized_from_raw<T: ?Sized, F, R>(ptr: *mut T, f: F) -> R
+    where F: FnOnce(TakeSlot<'_, T>) -> R
+{
+    TakeSlot::scoped(&mut *(ptr as *mut ManuallyDrop<T>), f)
+}
+
+/// Moves the value pointed to into its owned form.
+///
+/// # Safety
+///
+/// Same as `take_unsized_from_raw()`.
+pub unsafe fn take_from_raw<T: ?Sized + IntoOwned>(ptr: *mut T) -> T::Owned {
+    take_unsized_from_raw(ptr, |src| src.into_owned())
+}
+
+/// Moves the value pointed to into its owned form.
+///
+/// # Safety
+///
+/// Same as `take_unsized_from_raw()`.
+pub unsafe fn take_from_nonnull<T: ?Sized + IntoOwned>(ptr: NonNull<T>) -> T::Owned {
+    take_from_raw(ptr.as_ptr())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use alloc::{boxed::Box, vec::Vec};
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_take_from_raw() {
+        let check = DropCheck::new();
+
+        let boxed: Box<[_]> = vec![check.token(), check.token()].into_boxed_slice();
+        let ptr = Box::into_raw(boxed);
+        let taken: Vec<_> = unsafe { take_from_raw(ptr) };
+        assert!(check.none_dropped());
+
+        drop(unsafe { Box::from_raw(ptr as *mut [ManuallyDrop<dropcheck::DropToken>]) });
+        assert!(check.none_dropped());
+        drop(taken);
+        assert!(check.all_dropped());
+    }
+}
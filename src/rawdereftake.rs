@@ -0,0 +1,241 @@
+use core::ops;
+use core::pin::Pin;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::{mem::ManuallyDrop, ptr};
+
+#[cfg(feature = "allocator_api")]
+use alloc::alloc::Allocator;
+
+use super::*;
+
+/// The raw parts of a smart pointer, from which `DerefTake` is implemented automatically.
+///
+/// This lets custom smart pointers implement `DerefTake` by only taking themselves apart: moving
+/// the value out, and panic safety, are handled by the blanket impl.
+///
+/// # Safety
+///
+/// `into_raw_parts()` must return a pointer to a valid value the caller then owns, which stays
+/// valid until it's passed to `dealloc()`. `dealloc()` must free the memory without dropping the
+/// value.
+pub unsafe trait RawDerefTake : ops::Deref + Sized {
+    /// What's needed to free the memory, besides the pointer.
+    type DeallocInfo;
+
+    /// Consumes the pointer, returning a raw pointer to the value and the deallocation info.
+    fn into_raw_parts(self) -> (*mut Self::Target, Self::DeallocInfo);
+
+    /// Frees the memory, without dropping the value.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `info` must have come from `into_raw_parts()`, and the value must no longer be
+    /// used.
+    unsafe fn dealloc(ptr: *mut Self::Target, info: Self::DeallocInfo);
+
+    /// Takes ownership, as per `DerefTake::deref_take()`.
+    ///
+    /// Override this if the pointer can be turned into the owned form more cheaply than by moving
+    /// the value.
+    fn raw_deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.deref_take_unsized(|src| src.into_owned())
+    }
+
+    /// Takes ownership, as per `DerefTake::deref_take_as()`.
+    ///
+    /// Like `raw_deref_take()`, override this if there's a cheaper conversion.
+    fn raw_deref_take_as<O>(self) -> O
+        where Self::Target: IntoOwnedAs<O>
+    {
+        self.deref_take_unsized(|src| unsafe { Self::Target::into_owned_as_unchecked(TakeSlot::into_raw(src)) })
+    }
+}
+
+unsafe impl<P: RawDerefTake> DerefTake for P {
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.raw_deref_take()
+    }
+
+    fn deref_take_as<O>(self) -> O
+        where Self::Target: IntoOwnedAs<O>
+    {
+        self.raw_deref_take_as()
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        // Frees the memory even if the closure panics.
+        struct Dealloc<P: RawDerefTake> {
+            ptr: *mut P::Target,
+            info: Option<P::DeallocInfo>,
+        }
+
+        impl<P: RawDerefTake> Drop for Dealloc<P> {
+            fn drop(&mut self) {
+                if let Some(info) = self.info.take() {
+                    unsafe { P::dealloc(self.ptr, info) }
+                }
+            }
+        }
+
+        let (ptr, info) = self.into_raw_parts();
+        let _guard = Dealloc::<P> { ptr, info: Some(info) };
+        unsafe { raw::take_unsized_from_raw(ptr, f) }
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+unsafe impl<T: ?Sized> RawDerefTake for Box<T> {
+    type DeallocInfo = ();
+
+    fn into_raw_parts(self) -> (*mut T, ()) {
+        (Box::into_raw(self), ())
+    }
+
+    unsafe fn dealloc(ptr: *mut T, _: ()) {
+        drop(Box::from_raw(ptr as *mut ManuallyDrop<T>))
+    }
+
+    fn raw_deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        Self::Target::box_into_owned(self)
+    }
+
+    fn raw_deref_take_as<O>(self) -> O
+        where Self::Target: IntoOwnedAs<O>
+    {
+        Self::Target::box_into_owned_as(self)
+    }
+}
+
+/// `IntoOwned::box_into_owned()` only handles the global allocator, so the value is always moved.
+#[cfg(feature = "allocator_api")]
+unsafe impl<T: ?Sized, A: Allocator> RawDerefTake for Box<T, A> {
+    type DeallocInfo = A;
+
+    fn into_raw_parts(self) -> (*mut T, A) {
+        Box::into_raw_with_allocator(self)
+    }
+
+    unsafe fn dealloc(ptr: *mut T, alloc: A) {
+        drop(Box::from_raw_in(ptr as *mut ManuallyDrop<T>, alloc))
+    }
+}
+
+/// The deallocation info is the capacity. `deref_take()` returns the `Vec` itself.
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+unsafe impl<T> RawDerefTake for Vec<T> {
+    type DeallocInfo = usize;
+
+    fn into_raw_parts(self) -> (*mut [T], usize) {
+        let mut this = ManuallyDrop::new(self);
+        (ptr::slice_from_raw_parts_mut(this.as_mut_ptr(), this.len()), this.capacity())
+    }
+
+    unsafe fn dealloc(ptr: *mut [T], capacity: usize) {
+        drop(Vec::from_raw_parts(ptr as *mut T, 0, capacity))
+    }
+
+    fn raw_deref_take(self) -> <[T] as IntoOwned>::Owned {
+        self
+    }
+}
+
+/// The deallocation info is the capacity and the allocator.
+#[cfg(feature = "allocator_api")]
+unsafe impl<T, A: Allocator> RawDerefTake for Vec<T, A> {
+    type DeallocInfo = (usize, A);
+
+    fn into_raw_parts(self) -> (*mut [T], (usize, A)) {
+        let (ptr, len, capacity, alloc) = self.into_raw_parts_with_alloc();
+        (ptr::slice_from_raw_parts_mut(ptr, len), (capacity, alloc))
+    }
+
+    unsafe fn dealloc(ptr: *mut [T], (capacity, alloc): (usize, A)) {
+        drop(Vec::from_raw_parts_in(ptr as *mut T, 0, capacity, alloc))
+    }
+}
+
+unsafe impl<P: RawDerefTake> RawDerefTake for Pin<P>
+    where P::Target: Unpin
+{
+    type DeallocInfo = P::DeallocInfo;
+
+    fn into_raw_parts(self) -> (*mut P::Target, P::DeallocInfo) {
+        Pin::into_inner(self).into_raw_parts()
+    }
+
+    unsafe fn dealloc(ptr: *mut P::Target, info: P::DeallocInfo) {
+        P::dealloc(ptr, info)
+    }
+
+    fn raw_deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        Pin::into_inner(self).raw_deref_take()
+    }
+
+    fn raw_deref_take_as<O>(self) -> O
+        where Self::Target: IntoOwnedAs<O>
+    {
+        Pin::into_inner(self).raw_deref_take_as()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    /// A minimal smart pointer, counting deallocations.
+    struct Counted<'a, T>(Box<T>, &'a core::cell::Cell<usize>);
+
+    impl<T> ops::Deref for Counted<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.0
+        }
+    }
+
+    unsafe impl<'a, T> RawDerefTake for Counted<'a, T> {
+        type DeallocInfo = &'a core::cell::Cell<usize>;
+
+        fn into_raw_parts(self) -> (*mut T, Self::DeallocInfo) {
+            (Box::into_raw(self.0), self.1)
+        }
+
+        unsafe fn dealloc(ptr: *mut T, freed: Self::DeallocInfo) {
+            drop(Box::from_raw(ptr as *mut ManuallyDrop<T>));
+            freed.set(freed.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_blanket() {
+        let check = DropCheck::new();
+        let freed = core::cell::Cell::new(0);
+
+        let token: DropToken = Counted(Box::new(check.token()), &freed).deref_take();
+        assert_eq!(freed.get(), 1);
+        assert!(check.none_dropped());
+        drop(token);
+        assert!(check.all_dropped());
+
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            Counted(Box::new(1u8), &freed).deref_take_unsized(|_| panic!())
+        }));
+        assert!(r.is_err());
+        assert_eq!(freed.get(), 2);
+    }
+}
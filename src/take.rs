@@ -1,6 +1,33 @@
-use super::{IntoOwned, DerefTake};
+use super::{IntoOwned, IntoOwnedAs, DerefTake, TakeSlot};
 
-use std::mem::ManuallyDrop;
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ptr;
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    collections::{BinaryHeap, LinkedList, VecDeque},
+    ffi::CString,
+    rc::Rc,
+    string::String,
+    sync::Arc,
+    vec::{self, Vec},
+};
+#[cfg(feature = "alloc")]
+use core::{ffi::CStr, pin::Pin};
+
+#[cfg(feature = "allocator_api")]
+use alloc::alloc::Allocator;
+
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
+use std::sync::{Mutex, PoisonError, RwLock};
 
 /// A trait for taking data.
 ///
@@ -14,9 +41,20 @@ pub unsafe trait Take<T: ?Sized> : Sized {
     /// Takes ownership of `Sized` type.
     fn take_sized(self) -> T
         where T: Sized
+    {
+        self.take_unsized(|src| src.read())
+    }
+
+    /// Takes ownership of `Sized` type, moving it directly into `dest`.
+    ///
+    /// Unlike `take_sized()` the value isn't returned on the stack, which avoids an extra copy for
+    /// large values. Any previous value in `dest` is overwritten without being dropped.
+    fn take_into(self, dest: &mut MaybeUninit<T>) -> &mut T
+        where T: Sized
     {
         self.take_unsized(|src| unsafe {
-            (src as *const _ as *const T).read()
+            ptr::copy_nonoverlapping(TakeSlot::as_ptr(&src), dest.as_mut_ptr(), 1);
+            &mut *dest.as_mut_ptr()
         })
     }
 
@@ -24,53 +62,524 @@ pub unsafe trait Take<T: ?Sized> : Sized {
     fn take_owned(self) -> T::Owned
         where T: IntoOwned
     {
-        self.take_unsized(|src| unsafe { T::into_owned_unchecked(src) })
+        self.take_unsized(|src| src.into_owned())
+    }
+
+    /// Takes ownership of an unsized type, in the owned form of your choice.
+    fn take_owned_as<O>(self) -> O
+        where T: IntoOwnedAs<O>
+    {
+        self.take_unsized(|src| unsafe { T::into_owned_as_unchecked(TakeSlot::into_raw(src)) })
     }
 
     /// Takes ownership of an unsized type with the aid of a closure.
     ///
-    /// The closure is called with an mutable reference to `ManuallyDrop<T>`. After the closure
+    /// The closure is called with a `TakeSlot<T>` to move the value out of. After the closure
     /// returns the memory occupied by the value will be deallocated, but `drop()` will *not* be
     /// called on the value itself.
     ///
     /// `take_sized()` and `take_owned()` are implemented in terms of this.
     fn take_unsized<F,R>(self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<T>) -> R;
+        where F: FnOnce(TakeSlot<'_, T>) -> R;
 }
 
 unsafe impl<T> Take<T> for T {
     fn take_unsized<F,R>(self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<T>) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
     {
         let mut this = ManuallyDrop::new(self);
-        f(&mut this)
+        unsafe { TakeSlot::scoped(&mut this, f) }
     }
 }
 
 unsafe impl<T> Take<T> for ManuallyDrop<T> {
     fn take_unsized<F,R>(self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<T>) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
     {
         self.deref_take_unsized(f)
     }
 }
 
-unsafe impl<T: ?Sized + IntoOwned> Take<T> for Box<T> {
+unsafe impl<T> Take<T> for Cell<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        ManuallyDrop::new(self.into_inner()).deref_take_unsized(f)
+    }
+}
+
+unsafe impl<T> Take<T> for RefCell<T> {
     fn take_unsized<F,R>(self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<T>) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        ManuallyDrop::new(self.into_inner()).deref_take_unsized(f)
+    }
+}
+
+/// Panics if the value is `None`. See `TryTake` for a non-panicking version.
+unsafe impl<T> Take<T> for Option<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        match self {
+            Some(value) => value.take_unsized(f),
+            None => panic!("tried to take from a `None` value"),
+        }
+    }
+}
+
+/// Panics if the value is an `Err`. See `TryTake` for a non-panicking version.
+unsafe impl<T, E: fmt::Debug> Take<T> for Result<T, E> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        match self {
+            Ok(value) => value.take_unsized(f),
+            Err(err) => panic!("tried to take from an `Err` value: {:?}", err),
+        }
+    }
+}
+
+/// Poisoning is ignored, as the value is being taken out of the lock anyway.
+#[cfg(feature = "std")]
+unsafe impl<T> Take<T> for Mutex<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        let inner = self.into_inner().unwrap_or_else(PoisonError::into_inner);
+        ManuallyDrop::new(inner).deref_take_unsized(f)
+    }
+}
+
+/// Poisoning is ignored, as the value is being taken out of the lock anyway.
+#[cfg(feature = "std")]
+unsafe impl<T> Take<T> for RwLock<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        let inner = self.into_inner().unwrap_or_else(PoisonError::into_inner);
+        ManuallyDrop::new(inner).deref_take_unsized(f)
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+unsafe impl<T: ?Sized> Take<T> for Box<T> {
+    fn take_owned(self) -> T::Owned
+        where T: IntoOwned
+    {
+        T::box_into_owned(self)
+    }
+
+    fn take_owned_as<O>(self) -> O
+        where T: IntoOwnedAs<O>
+    {
+        T::box_into_owned_as(self)
+    }
+
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
     {
         self.deref_take_unsized(f)
     }
 }
 
+#[cfg(feature = "allocator_api")]
+unsafe impl<T: ?Sized, A: Allocator> Take<T> for Box<T, A> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+unsafe impl<T, const N: usize> Take<[T]> for [T; N] {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        let mut this = ManuallyDrop::new(self);
+        let this: &mut ManuallyDrop<[T]> = &mut this;
+        unsafe { TakeSlot::scoped(this, f) }
+    }
+}
+
+/// Reuses the allocation.
+#[cfg(feature = "alloc")]
+unsafe impl<T, const N: usize> Take<[T]> for Box<[T; N]> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        let this: Box<[T]> = self;
+        this.take_unsized(f)
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+unsafe impl<T: ?Sized + Unpin> Take<T> for Pin<Box<T>> {
+    fn take_owned(self) -> T::Owned
+        where T: IntoOwned
+    {
+        T::box_into_owned(Pin::into_inner(self))
+    }
+
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+unsafe impl<T: ?Sized + Unpin, A: Allocator> Take<T> for Pin<Box<T, A>> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
 unsafe impl<T> Take<[T]> for Vec<T> {
     fn take_unsized<F,R>(self, f: F) -> R
-        where F: FnOnce(&mut ManuallyDrop<[T]>) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+unsafe impl<T, A: Allocator> Take<[T]> for Vec<T, A> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+/// Reuses the allocation, rotating the elements into place if needed.
+#[cfg(feature = "alloc")]
+unsafe impl<T> Take<[T]> for VecDeque<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        Vec::from(self).deref_take_unsized(f)
+    }
+}
+
+/// The elements are taken in an arbitrary order.
+#[cfg(feature = "alloc")]
+unsafe impl<T> Take<[T]> for BinaryHeap<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.into_vec().deref_take_unsized(f)
+    }
+}
+
+/// The elements are moved into a contiguous allocation first.
+#[cfg(feature = "alloc")]
+unsafe impl<T> Take<[T]> for LinkedList<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.into_iter().collect::<Vec<T>>().deref_take_unsized(f)
+    }
+}
+
+/// Takes the elements not yet yielded. The allocation may be reused, but std doesn't guarantee
+/// it; the elements may be moved to a new one instead.
+#[cfg(feature = "alloc")]
+unsafe impl<T> Take<[T]> for vec::IntoIter<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.collect::<Vec<T>>().deref_take_unsized(f)
+    }
+}
+
+/// Takes the elements not yet yielded, moving them into a new allocation.
+#[cfg(feature = "alloc")]
+unsafe impl<T> Take<[T]> for vec::Drain<'_, T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.collect::<Vec<T>>().deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Clone> Take<T> for Rc<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Clone> Take<T> for Arc<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Clone> Take<[T]> for Rc<[T]> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Clone> Take<[T]> for Arc<[T]> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl Take<str> for Rc<str> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, str>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl Take<str> for Arc<str> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, str>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<'a, B> Take<B> for Cow<'a, B>
+    where B: ?Sized + IntoOwned + ToOwned<Owned = <B as IntoOwned>::Owned>
+{
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, B>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl Take<str> for String {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, str>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl Take<CStr> for CString {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, CStr>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "std")]
+unsafe impl Take<OsStr> for OsString {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, OsStr>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(feature = "std")]
+unsafe impl Take<Path> for PathBuf {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Path>) -> R
     {
         self.deref_take_unsized(f)
     }
 }
 
+/// Takes the value out of a place, writing back the result of a closure.
+///
+/// If the closure panics there's no value to leave behind, so the process is aborted. Use
+/// `take_or_restore_with()` to restore a fallback value instead.
+pub fn take_or_restore<T, F>(dest: &mut T, f: F)
+    where F: FnOnce(T) -> T
+{
+    take_or_restore_with(dest, f, || panic!("take_or_restore() closure panicked; aborting"))
+}
+
+/// Takes the value out of a place, writing back the result of a closure.
+///
+/// If the closure panics the place is refilled with `fallback()` and the panic is propagated. If
+/// `fallback()` panics too, the process is aborted.
+pub fn take_or_restore_with<T, F, G>(dest: &mut T, f: F, fallback: G)
+    where F: FnOnce(T) -> T,
+          G: FnOnce() -> T
+{
+    struct Restore<T, G: FnOnce() -> T> {
+        dest: *mut T,
+        fallback: Option<G>,
+    }
+
+    impl<T, G: FnOnce() -> T> Drop for Restore<T, G> {
+        fn drop(&mut self) {
+            // Only reached on panic. Panicking again here, while unwinding, aborts.
+            if let Some(fallback) = self.fallback.take() {
+                unsafe { self.dest.write(fallback()) }
+            }
+        }
+    }
+
+    let dest: *mut T = dest;
+    let mut restore = Restore { dest, fallback: Some(fallback) };
+    unsafe {
+        let new = f(dest.read());
+        restore.fallback = None;
+        dest.write(new);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+    use std::thread;
+
+    fn take_string(src: impl Take<String>) -> String {
+        src.take_sized()
+    }
+
+    #[test]
+    fn test_cells() {
+        assert_eq!(take_string(Cell::new(String::from("cell"))), "cell");
+        assert_eq!(take_string(RefCell::new(String::from("refcell"))), "refcell");
+        assert_eq!(take_string(Mutex::new(String::from("mutex"))), "mutex");
+        assert_eq!(take_string(RwLock::new(String::from("rwlock"))), "rwlock");
+    }
+
+    #[test]
+    fn test_option_result() {
+        assert_eq!(take_string(Some(String::from("some"))), "some");
+        assert_eq!(take_string(Ok::<_, ()>(String::from("ok"))), "ok");
+
+        let r = std::panic::catch_unwind(|| take_string(Err::<String, _>("failed")));
+        let msg = r.unwrap_err();
+        assert_eq!(msg.downcast_ref::<String>().unwrap(), "tried to take from an `Err` value: \"failed\"");
+        assert!(std::panic::catch_unwind(|| take_string(None::<String>)).is_err());
+    }
+
+    #[test]
+    fn test_poisoned() {
+        let mutex = Arc::new(Mutex::new(String::from("poisoned")));
+        let mutex2 = Arc::clone(&mutex);
+        let _ = thread::spawn(move || {
+            let _guard = mutex2.lock().unwrap();
+            panic!();
+        }).join();
+        assert!(mutex.is_poisoned());
+
+        let mutex = Arc::try_unwrap(mutex).unwrap();
+        assert_eq!(take_string(mutex), "poisoned");
+    }
+
+    #[test]
+    fn test_collections() {
+        let mut deque: VecDeque<String> = VecDeque::with_capacity(4);
+        deque.push_back(String::from("b"));
+        deque.push_front(String::from("a"));
+        assert_eq!(Take::<[String]>::take_owned(deque), ["a", "b"]);
+
+        let heap = BinaryHeap::from(vec![3u8, 1, 2]);
+        let mut v = Take::<[u8]>::take_owned(heap);
+        v.sort();
+        assert_eq!(v, [1, 2, 3]);
+
+        let list: LinkedList<String> = ["x", "y"].iter().map(|s| String::from(*s)).collect();
+        assert_eq!(Take::<[String]>::take_owned(list), ["x", "y"]);
+    }
+
+    #[test]
+    fn test_vec_iters() {
+        let v: Vec<String> = (0 .. 4).map(|i| i.to_string()).collect();
+        let mut iter = v.into_iter();
+        assert_eq!(iter.next().unwrap(), "0");
+        assert_eq!(Take::<[String]>::take_owned(iter), ["1", "2", "3"]);
+
+        let mut v: Vec<String> = (0 .. 4).map(|i| i.to_string()).collect();
+        let mut drain = v.drain(1 .. 3);
+        assert_eq!(drain.next_back().unwrap(), "2");
+        assert_eq!(Take::<[String]>::take_owned(drain), ["1"]);
+        assert_eq!(v, ["0", "3"]);
+    }
+
+    #[test]
+    fn test_array() {
+        fn take_vec<C: Take<[String]>>(src: C) -> Vec<String> {
+            src.take_owned()
+        }
+
+        assert_eq!(take_vec([String::from("a"), String::from("b")]), ["a", "b"]);
+        assert_eq!(take_vec(Box::new([String::from("c")])), ["c"]);
+        assert_eq!(Take::<[u8]>::take_unsized([0u8; 16], |src| src.len()), 16);
+
+        let key: [u8; 32] = Box::new([7; 32]).deref_take();
+        assert_eq!(key, [7; 32]);
+        let key: [u8; 32] = Take::<[u8; 32]>::take_sized(Box::new([8; 32]));
+        assert_eq!(key, [8; 32]);
+    }
+
+    #[test]
+    fn test_box_dyn() {
+        use core::fmt::Display;
+
+        let boxed: Box<dyn Display> = Box::new(42u8);
+        let s = Take::<dyn Display>::take_unsized(boxed, |slot| slot.to_string());
+        assert_eq!(s, "42");
+    }
+
+    #[test]
+    fn test_pin() {
+        assert_eq!(take_string(Box::pin(String::from("pinned"))), "pinned");
+
+        let pinned: Pin<Box<str>> = Box::into_pin("pinned str".into());
+        assert_eq!(Take::<str>::take_owned(pinned), "pinned str");
+    }
+
+    #[test]
+    fn test_take_or_restore() {
+        let mut s = String::from("hello");
+        take_or_restore(&mut s, |s| s + " world");
+        assert_eq!(s, "hello world");
+
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            take_or_restore_with(&mut s, |_| panic!(), || String::from("restored"))
+        }));
+        assert!(r.is_err());
+        assert_eq!(s, "restored");
+    }
+
+    #[test]
+    fn test_take_into() {
+        let mut dest = MaybeUninit::<String>::uninit();
+        let s = Box::new(String::from("boxed")).take_into(&mut dest);
+        assert_eq!(s, "boxed");
+        unsafe { ptr::drop_in_place(s) };
+
+        let mut dest = MaybeUninit::<[u64; 512]>::uninit();
+        assert_eq!(Take::<[u64; 512]>::take_into(Box::new([7; 512]), &mut dest)[511], 7);
+    }
+}
+
 /*
 #[cfg(test)]
 mod test {
@@ -0,0 +1,159 @@
+use core::borrow::Borrow;
+use core::ops;
+
+#[cfg(feature = "alloc")]
+use alloc::borrow::ToOwned;
+
+use super::*;
+
+/// A borrowed or owned value.
+///
+/// Like `Cow`, but based on `IntoOwned` rather than `ToOwned`. Taking the value out of the
+/// `Owned` variant is a move; only the `Borrowed` variant needs to be cloned.
+pub enum TakeCow<'a, T: ?Sized + IntoOwned> {
+    /// Borrowed data.
+    Borrowed(&'a T),
+
+    /// Owned data.
+    Owned(T::Owned),
+}
+
+impl<'a, T: ?Sized + IntoOwned> TakeCow<'a, T> {
+    /// Returns true if the value is borrowed.
+    pub fn is_borrowed(&self) -> bool {
+        match self {
+            TakeCow::Borrowed(_) => true,
+            TakeCow::Owned(_) => false,
+        }
+    }
+
+    /// Returns true if the value is owned.
+    pub fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /// Takes the owned value, returning the borrow instead if the value isn't owned.
+    pub fn try_take(self) -> Result<T::Owned, &'a T> {
+        match self {
+            TakeCow::Borrowed(borrowed) => Err(borrowed),
+            TakeCow::Owned(owned) => Ok(owned),
+        }
+    }
+
+    /// Takes the owned value, cloning it if the value is borrowed.
+    #[cfg(feature = "alloc")]
+    pub fn take(self) -> <T as IntoOwned>::Owned
+        where T: ToOwned<Owned = <T as IntoOwned>::Owned>
+    {
+        match self {
+            TakeCow::Borrowed(borrowed) => borrowed.to_owned(),
+            TakeCow::Owned(owned) => owned,
+        }
+    }
+}
+
+impl<'a, T: ?Sized + IntoOwned> ops::Deref for TakeCow<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            TakeCow::Borrowed(borrowed) => borrowed,
+            TakeCow::Owned(owned) => owned.borrow(),
+        }
+    }
+}
+
+impl<'a, T: ?Sized + IntoOwned> From<&'a T> for TakeCow<'a, T> {
+    fn from(borrowed: &'a T) -> Self {
+        TakeCow::Borrowed(borrowed)
+    }
+}
+
+impl<'a, T: ?Sized + IntoOwned> Clone for TakeCow<'a, T>
+    where T::Owned: Clone
+{
+    fn clone(&self) -> Self {
+        match self {
+            TakeCow::Borrowed(borrowed) => TakeCow::Borrowed(borrowed),
+            TakeCow::Owned(owned) => TakeCow::Owned(owned.clone()),
+        }
+    }
+}
+
+impl<'a, T: ?Sized + IntoOwned + core::fmt::Debug> core::fmt::Debug for TakeCow<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            TakeCow::Borrowed(borrowed) => f.debug_tuple("Borrowed").field(borrowed).finish(),
+            TakeCow::Owned(owned) => f.debug_tuple("Owned").field(&owned.borrow()).finish(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<'a, T> DerefTake for TakeCow<'a, T>
+    where T: ?Sized + IntoOwned + ToOwned<Owned = <T as IntoOwned>::Owned>
+{
+    fn deref_take(self) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned
+    {
+        self.take()
+    }
+
+    fn deref_take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        self.take().take_unsized(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<'a, T> Take<T> for TakeCow<'a, T>
+    where T: ?Sized + IntoOwned + ToOwned<Owned = <T as IntoOwned>::Owned>
+{
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    #[test]
+    fn test_take() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let cow: TakeCow<DropToken> = TakeCow::Owned(token);
+        assert!(cow.is_owned());
+        let token = cow.try_take().ok().unwrap();
+        assert!(state.is_not_dropped());
+        drop(token);
+        assert!(state.is_dropped());
+
+        let s = String::from("hello");
+        let ptr = s.as_ptr();
+        let cow: TakeCow<str> = TakeCow::Owned(s);
+        assert_eq!(&*cow, "hello");
+        let s = cow.take();
+        assert_eq!(s.as_ptr(), ptr);
+
+        let cow: TakeCow<str> = TakeCow::from("borrowed");
+        assert!(cow.is_borrowed());
+        assert_eq!(cow.clone().try_take(), Err("borrowed"));
+        assert_eq!(cow.take(), "borrowed");
+    }
+
+    #[test]
+    fn test_deref_take() {
+        let cow: TakeCow<[u8]> = TakeCow::Owned(vec![1, 2, 3]);
+        assert_eq!(cow.deref_take_unsized(|src| src.len()), 3);
+
+        let cow: TakeCow<[u8]> = TakeCow::Borrowed(&[1, 2]);
+        assert_eq!(Take::<[u8]>::take_owned(cow), vec![1, 2]);
+    }
+}
@@ -0,0 +1,84 @@
+use super::*;
+
+/// Taking ownership of a value from a container, mirroring `From`.
+///
+/// Implemented for every `T` that can be taken from `C`. Usually it's more convenient to use
+/// `TakeInto` as a bound, in the way `Into` is used instead of `From`.
+pub trait TakeFrom<C> : Sized {
+    /// Takes ownership of the value in `src`.
+    fn take_from(src: C) -> Self;
+}
+
+impl<T, C: Take<T>> TakeFrom<C> for T {
+    fn take_from(src: C) -> T {
+        src.take_sized()
+    }
+}
+
+/// Taking ownership of the value in a container, mirroring `Into`.
+///
+/// This lets a function accept anything it can take a `T` from:
+///
+/// ```
+/// use core::mem::ManuallyDrop;
+/// use owned::TakeInto;
+///
+/// struct Config {
+///     verbose: bool,
+/// }
+///
+/// fn is_verbose(config: impl TakeInto<Config>) -> bool {
+///     config.into_taken().verbose
+/// }
+///
+/// assert!(is_verbose(Config { verbose: true }));
+/// assert!(!is_verbose(ManuallyDrop::new(Config { verbose: false })));
+/// ```
+///
+/// Shared containers such as `Rc<T>` are accepted too, cloning the value if they aren't the only
+/// owner.
+pub trait TakeInto<T> : Sized {
+    /// Takes ownership of the value in `self`.
+    fn into_taken(self) -> T;
+}
+
+impl<C, T: TakeFrom<C>> TakeInto<T> for C {
+    fn into_taken(self) -> T {
+        T::take_from(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use core::cell::RefCell;
+    use core::mem::ManuallyDrop;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Config(u8);
+
+    fn new(src: impl TakeInto<Config>) -> Config {
+        src.into_taken()
+    }
+
+    #[test]
+    fn test_take_into() {
+        assert_eq!(new(Config(1)), Config(1));
+        assert_eq!(new(ManuallyDrop::new(Config(2))), Config(2));
+        assert_eq!(new(RefCell::new(Config(3))), Config(3));
+        assert_eq!(new(Some(Config(4))), Config(4));
+        assert_eq!(Config::take_from(Config(5)), Config(5));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_box() {
+        use alloc::{boxed::Box, rc::Rc};
+        assert_eq!(new(Box::new(Config(6))), Config(6));
+
+        let rc = Rc::new(Config(7));
+        assert_eq!(new(Rc::clone(&rc)), Config(7));
+        assert_eq!(new(rc), Config(7));
+    }
+}
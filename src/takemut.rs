@@ -0,0 +1,129 @@
+use core::mem::{self, ManuallyDrop};
+
+use alloc::{
+    boxed::Box,
+    string::String,
+    vec::Vec,
+};
+
+use super::*;
+
+/// Taking ownership of the contents of a container through a mutable reference.
+///
+/// The container is left valid but empty.
+pub trait TakeMut {
+    /// The owned form of the contents.
+    type Owned;
+
+    /// Moves the contents out, leaving the container empty.
+    fn take_mut(&mut self) -> Self::Owned;
+}
+
+/// The `Vec` keeps its allocation.
+impl<T> TakeMut for Vec<T> {
+    type Owned = Vec<T>;
+
+    fn take_mut(&mut self) -> Vec<T> {
+        let mut taken = Vec::with_capacity(self.len());
+        taken.append(self);
+        taken
+    }
+}
+
+/// The `String` keeps its allocation.
+impl TakeMut for String {
+    type Owned = String;
+
+    fn take_mut(&mut self) -> String {
+        let taken = String::from(self.as_str());
+        self.clear();
+        taken
+    }
+}
+
+impl<T> TakeMut for Box<[T]> {
+    type Owned = Vec<T>;
+
+    fn take_mut(&mut self) -> Vec<T> {
+        mem::take(self).deref_take()
+    }
+}
+
+impl TakeMut for Box<str> {
+    type Owned = String;
+
+    fn take_mut(&mut self) -> String {
+        mem::take(self).deref_take()
+    }
+}
+
+/// Moves the elements out of a mutable slice, leaving an empty slice behind.
+///
+/// # Safety
+///
+/// The elements are moved out without the storage they live in being aware of it. The caller must
+/// ensure that storage will never drop or otherwise use the elements again, e.g. because it's an
+/// arena that doesn't run destructors.
+pub unsafe fn take_mut_slice<T>(slice: &mut &mut [T]) -> Vec<T> {
+    let taken: &mut [T] = mem::take(slice);
+    let taken = &mut *(taken as *mut [T] as *mut ManuallyDrop<[T]>);
+    <[T]>::into_owned_unchecked(taken)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_vec() {
+        let check = DropCheck::new();
+
+        let mut v = vec![check.token(); 10];
+        let cap = v.capacity();
+        let taken = v.take_mut();
+        assert!(v.is_empty());
+        assert_eq!(v.capacity(), cap);
+        assert_eq!(taken.len(), 10);
+        assert!(check.none_dropped());
+
+        drop(v);
+        assert!(check.none_dropped());
+        drop(taken);
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_strs() {
+        let mut s = String::from("hello");
+        assert_eq!(s.take_mut(), "hello");
+        assert_eq!(s, "");
+
+        let mut s: Box<str> = "hello".into();
+        assert_eq!(s.take_mut(), "hello");
+        assert_eq!(&*s, "");
+
+        let mut b: Box<[u8]> = Box::new([1, 2, 3]);
+        assert_eq!(b.take_mut(), [1, 2, 3]);
+        assert!(b.is_empty());
+    }
+
+    #[test]
+    fn test_slice() {
+        let check = DropCheck::new();
+
+        // Stand in for an arena that never drops its contents.
+        let mut arena = ManuallyDrop::new(vec![check.token(); 3]);
+        let mut slice: &mut [_] = &mut arena[..];
+
+        let taken = unsafe { take_mut_slice(&mut slice) };
+        assert!(slice.is_empty());
+        assert_eq!(taken.len(), 3);
+        drop(taken);
+        assert!(check.all_dropped());
+
+        // Free the arena's memory without dropping the moved-out elements.
+        unsafe { arena.set_len(0); ManuallyDrop::drop(&mut arena); }
+    }
+}
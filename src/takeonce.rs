@@ -0,0 +1,204 @@
+use core::cell::{Cell, UnsafeCell};
+use core::fmt;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use super::*;
+
+/// A cell holding a value that can be taken once.
+pub struct TakeOnce<T> {
+    value: Cell<Option<T>>,
+}
+
+impl<T> TakeOnce<T> {
+    /// Creates a new cell holding `value`.
+    pub const fn new(value: T) -> Self {
+        TakeOnce { value: Cell::new(Some(value)) }
+    }
+
+    /// Takes the value, returning `None` if it has already been taken.
+    pub fn try_take(&self) -> Option<T> {
+        self.value.take()
+    }
+
+    /// Takes the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value has already been taken.
+    pub fn take(&self) -> T {
+        self.try_take().expect("TakeOnce value already taken")
+    }
+
+    /// Returns true if the value has been taken.
+    pub fn is_taken(&self) -> bool {
+        let value = self.value.take();
+        let taken = value.is_none();
+        self.value.set(value);
+        taken
+    }
+}
+
+impl<T> fmt::Debug for TakeOnce<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TakeOnce").field("taken", &self.is_taken()).finish()
+    }
+}
+
+/// Panics if the value has already been taken.
+unsafe impl<T> Take<T> for TakeOnce<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        TakeOnce::take(&self).take_unsized(f)
+    }
+}
+
+const EMPTY: u8 = 0;
+const WRITING: u8 = 1;
+const FULL: u8 = 2;
+const TAKEN: u8 = 3;
+
+/// A thread-safe cell whose value can be set once and taken once, without locking.
+pub struct AtomicTakeOnce<T> {
+    state: AtomicU8,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// The value is only ever accessed by the one thread that set or took it.
+unsafe impl<T: Send> Send for AtomicTakeOnce<T> {}
+unsafe impl<T: Send> Sync for AtomicTakeOnce<T> {}
+
+impl<T> AtomicTakeOnce<T> {
+    /// Creates a new cell holding `value`.
+    pub const fn new(value: T) -> Self {
+        AtomicTakeOnce {
+            state: AtomicU8::new(FULL),
+            value: UnsafeCell::new(MaybeUninit::new(value)),
+        }
+    }
+
+    /// Creates a new empty cell, to be filled with `set()`.
+    pub const fn empty() -> Self {
+        AtomicTakeOnce {
+            state: AtomicU8::new(EMPTY),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Sets the value, returning it back if the cell was ever set before.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        match self.state.compare_exchange(EMPTY, WRITING, Ordering::Acquire, Ordering::Relaxed) {
+            Ok(_) => {
+                unsafe { (*self.value.get()).as_mut_ptr().write(value) };
+                self.state.store(FULL, Ordering::Release);
+                Ok(())
+            }
+            Err(_) => Err(value),
+        }
+    }
+
+    /// Takes the value, returning `None` if it hasn't been set or has already been taken.
+    pub fn try_take(&self) -> Option<T> {
+        match self.state.compare_exchange(FULL, TAKEN, Ordering::Acquire, Ordering::Relaxed) {
+            Ok(_) => Some(unsafe { (*self.value.get()).as_ptr().read() }),
+            Err(_) => None,
+        }
+    }
+
+    /// Takes the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value hasn't been set or has already been taken.
+    pub fn take(&self) -> T {
+        self.try_take().expect("AtomicTakeOnce value not set or already taken")
+    }
+
+    /// Returns true if the value has been taken.
+    pub fn is_taken(&self) -> bool {
+        self.state.load(Ordering::Acquire) == TAKEN
+    }
+}
+
+impl<T> Drop for AtomicTakeOnce<T> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() == FULL {
+            unsafe { self.value.get_mut().as_mut_ptr().drop_in_place() }
+        }
+    }
+}
+
+impl<T> fmt::Debug for AtomicTakeOnce<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AtomicTakeOnce").field("taken", &self.is_taken()).finish()
+    }
+}
+
+/// Panics if the value hasn't been set or has already been taken.
+unsafe impl<T> Take<T> for AtomicTakeOnce<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        let mut this = ManuallyDrop::new(self);
+        assert_eq!(*this.state.get_mut(), FULL, "AtomicTakeOnce value not set or already taken");
+        let value = unsafe { &mut *(this.value.get_mut() as *mut MaybeUninit<T> as *mut ManuallyDrop<T>) };
+        unsafe { TakeSlot::scoped(value, f) }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    use std::sync::Arc;
+    use std::thread;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    #[test]
+    fn test_take_once() {
+        let cell = TakeOnce::new(String::from("once"));
+        assert!(!cell.is_taken());
+        assert_eq!(cell.take(), "once");
+        assert!(cell.is_taken());
+        assert_eq!(cell.try_take(), None);
+
+        let cell = TakeOnce::new(String::from("take"));
+        assert_eq!(Take::<String>::take_sized(cell), "take");
+    }
+
+    #[test]
+    fn test_atomic() {
+        let check = DropCheck::new();
+
+        let cell = Arc::new(AtomicTakeOnce::empty());
+        let handles: Vec<_> = (0 .. 4).map(|_| {
+            let cell = Arc::clone(&cell);
+            thread::spawn(move || cell.try_take().is_some())
+        }).collect();
+
+        let (token, state) = check.pair();
+        assert!(cell.set(token).is_ok());
+        assert!(cell.set(check.token()).is_err());
+
+        let taken = handles.into_iter().map(|h| h.join().unwrap()).filter(|t| *t).count();
+        let taken = taken + cell.try_take().map_or(0, |_| 1);
+        assert_eq!(taken, 1);
+        assert!(state.is_dropped());
+    }
+
+    #[test]
+    fn test_atomic_drop() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let cell = AtomicTakeOnce::new(token);
+        let token: DropToken = cell.take_sized();
+        assert!(state.is_not_dropped());
+        drop(token);
+
+        drop(AtomicTakeOnce::new(check.token()));
+        assert!(check.all_dropped());
+    }
+}
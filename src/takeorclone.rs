@@ -0,0 +1,123 @@
+use core::cell::{Cell, RefCell};
+use core::ffi::CStr;
+use core::mem::ManuallyDrop;
+use core::pin::Pin;
+
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    collections::{BinaryHeap, LinkedList, VecDeque},
+    ffi::CString,
+    rc::Rc,
+    string::String,
+    sync::Arc,
+    vec::{self, Vec},
+};
+
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
+use std::sync::{Mutex, RwLock};
+
+use super::*;
+
+/// Taking ownership by moving out of a container, or by cloning from a reference.
+///
+/// Implemented for references, where `T: ToOwned`, and for the containers that implement `Take`,
+/// so a single bound covers both cases. A blanket impl over `Take` isn't possible, as it would
+/// overlap with the impl for references; `#[derive(Take)]` implements this as well.
+pub trait TakeOrClone<T: ?Sized + IntoOwned> : Sized {
+    /// Takes ownership of the owned version of `T`, cloning if `self` is a reference.
+    fn take_or_clone(self) -> T::Owned;
+}
+
+impl<T> TakeOrClone<T> for &T
+    where T: ?Sized + IntoOwned + ToOwned<Owned = <T as IntoOwned>::Owned>
+{
+    fn take_or_clone(self) -> <T as IntoOwned>::Owned {
+        self.to_owned()
+    }
+}
+
+/// Implements `TakeOrClone` for a container in terms of its `Take` impl.
+///
+/// Exported via `__private` for `#[derive(Take)]`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_take_or_clone {
+    ($(#[$attr:meta])* [$($g:tt)*] $ty:ty => $t:ty $(where $($w:tt)*)?) => {
+        $(#[$attr])*
+        impl<$($g)*> $crate::TakeOrClone<$t> for $ty
+            where $ty: $crate::Take<$t>,
+                  $t: $crate::IntoOwned,
+                  $($($w)*)?
+        {
+            fn take_or_clone(self) -> <$t as $crate::IntoOwned>::Owned {
+                $crate::Take::<$t>::take_owned(self)
+            }
+        }
+    };
+}
+pub(crate) use crate::__impl_take_or_clone as impl_take_or_clone;
+
+impl_take_or_clone!([T] T => T);
+impl_take_or_clone!([T] ManuallyDrop<T> => T);
+impl_take_or_clone!([T] Cell<T> => T);
+impl_take_or_clone!([T] RefCell<T> => T);
+impl_take_or_clone!([T] Option<T> => T);
+impl_take_or_clone!([T, E] Result<T, E> => T);
+impl_take_or_clone!(#[cfg(feature = "std")] [T] Mutex<T> => T);
+impl_take_or_clone!(#[cfg(feature = "std")] [T] RwLock<T> => T);
+impl_take_or_clone!([T: ?Sized] Box<T> => T);
+impl_take_or_clone!([T: ?Sized] Pin<Box<T>> => T);
+impl_take_or_clone!([T, const N: usize] [T; N] => [T]);
+impl_take_or_clone!([T, const N: usize] Box<[T; N]> => [T]);
+impl_take_or_clone!([T] Vec<T> => [T]);
+impl_take_or_clone!([T] VecDeque<T> => [T]);
+impl_take_or_clone!([T] BinaryHeap<T> => [T]);
+impl_take_or_clone!([T] LinkedList<T> => [T]);
+impl_take_or_clone!([T] vec::IntoIter<T> => [T]);
+impl_take_or_clone!(['a, T] vec::Drain<'a, T> => [T]);
+impl_take_or_clone!([T] Rc<T> => T);
+impl_take_or_clone!([T] Arc<T> => T);
+impl_take_or_clone!([T] Rc<[T]> => [T]);
+impl_take_or_clone!([T] Arc<[T]> => [T]);
+impl_take_or_clone!([] Rc<str> => str);
+impl_take_or_clone!([] Arc<str> => str);
+impl_take_or_clone!(['a, B: ?Sized + ToOwned] Cow<'a, B> => B);
+impl_take_or_clone!(['a, T: ?Sized + ToOwned] TakeCow<'a, T> => T);
+impl_take_or_clone!([] String => str);
+impl_take_or_clone!([] CString => CStr);
+impl_take_or_clone!(#[cfg(feature = "std")] [] OsString => OsStr);
+impl_take_or_clone!(#[cfg(feature = "std")] [] PathBuf => Path);
+impl_take_or_clone!([T: ?Sized] ForeignBox<T> => T);
+impl_take_or_clone!([T] TakeOnce<T> => T);
+impl_take_or_clone!([T] AtomicTakeOnce<T> => T);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    fn take_or_clone_str(src: impl TakeOrClone<str>) -> String {
+        src.take_or_clone()
+    }
+
+    #[test]
+    fn test_take_or_clone() {
+        assert_eq!(take_or_clone_str("ref"), "ref");
+        assert_eq!(take_or_clone_str(String::from("string")), "string");
+        assert_eq!(take_or_clone_str(Box::<str>::from("boxed")), "boxed");
+        assert_eq!(take_or_clone_str(Cow::Borrowed("cow")), "cow");
+
+        let check = DropCheck::new();
+        let (token, state) = check.pair();
+        let token = TakeOrClone::<DropToken>::take_or_clone(Box::new(token));
+        assert!(state.is_not_dropped());
+        drop(token);
+        assert!(state.is_dropped());
+    }
+}
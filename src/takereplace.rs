@@ -0,0 +1,97 @@
+use core::mem;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::*;
+
+/// Taking ownership out of a place, by leaving something else behind.
+///
+/// Like `mem::take()` and `mem::replace()`, but also applicable to unsized types such as slices,
+/// which are refilled element by element.
+pub trait TakeReplace : IntoOwned {
+    /// What is left behind in place of the taken value.
+    ///
+    /// `Self` for sized types, and the element type for slices.
+    type Filler;
+
+    /// Takes the value, refilling the place with values from a closure.
+    ///
+    /// If the closure panics, the place is left fully initialized, with some mixture of the old
+    /// and new values.
+    fn take_replace_with<F>(&mut self, f: F) -> Self::Owned
+        where F: FnMut() -> Self::Filler;
+
+    /// Takes the value, leaving the default value behind.
+    fn take_default(&mut self) -> Self::Owned
+        where Self::Filler: Default
+    {
+        self.take_replace_with(Default::default)
+    }
+
+    /// Takes the value, leaving `replacement` behind.
+    fn take_replace(&mut self, replacement: Self) -> Self
+        where Self: Sized
+    {
+        mem::replace(self, replacement)
+    }
+}
+
+impl<T> TakeReplace for T {
+    type Filler = T;
+
+    fn take_replace_with<F>(&mut self, mut f: F) -> T
+        where F: FnMut() -> T
+    {
+        mem::replace(self, f())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> TakeReplace for [T] {
+    type Filler = T;
+
+    fn take_replace_with<F>(&mut self, mut f: F) -> Vec<T>
+        where F: FnMut() -> T
+    {
+        let mut r = Vec::with_capacity(self.len());
+        for elem in self.iter_mut() {
+            r.push(mem::replace(elem, f()));
+        }
+        r
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sized() {
+        let mut s = String::from("hello");
+        assert_eq!(s.take_default(), "hello");
+        assert_eq!(s, "");
+
+        let mut n = 1;
+        assert_eq!(n.take_replace(2), 1);
+        assert_eq!(n, 2);
+        assert_eq!(n.take_replace_with(|| 3), 2);
+        assert_eq!(n, 3);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_slice() {
+        let mut buf = [String::from("a"), String::from("b")];
+        let slice: &mut [String] = &mut buf;
+        let taken: Vec<String> = slice.take_default();
+        assert_eq!(taken, ["a", "b"]);
+        assert_eq!(buf, ["", ""]);
+
+        let mut n = 0;
+        let slice: &mut [i32] = &mut [1, 2, 3];
+        let taken = slice.take_replace_with(|| { n += 1; n * 10 });
+        assert_eq!(taken, [1, 2, 3]);
+        assert_eq!(slice, [10, 20, 30]);
+    }
+}
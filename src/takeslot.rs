@@ -0,0 +1,158 @@
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops;
+use core::ptr;
+
+use super::*;
+
+/// A value being taken, passed to the closures of `Take::take_unsized()` and friends.
+///
+/// The slot is consumed by moving the value out with `read()` or `into_owned()`. If the slot is
+/// dropped instead, the value is leaked: `drop()` is *not* called on it.
+pub struct TakeSlot<'a, T: ?Sized> {
+    inner: &'a mut ManuallyDrop<T>,
+}
+
+impl<'a, T: ?Sized> TakeSlot<'a, T> {
+    /// Creates a new slot.
+    ///
+    /// # Safety
+    ///
+    /// The value must be owned by the slot: once the slot is created the caller must not drop or
+    /// otherwise use the value.
+    pub unsafe fn new(inner: &'a mut ManuallyDrop<T>) -> Self {
+        #[cfg(feature = "debug-leakcheck")]
+        crate::leakcheck::slot_created::<T>(&**inner);
+
+        TakeSlot { inner }
+    }
+
+    /// Calls `f` with a new slot, for the take calls of this crate's containers.
+    ///
+    /// Unlike with `new()`, the leakcheck tracks the slot until `f` returns.
+    ///
+    /// # Safety
+    ///
+    /// As with `new()`.
+    pub(crate) unsafe fn scoped<F, R>(inner: &'a mut ManuallyDrop<T>, f: F) -> R
+        where F: FnOnce(Self) -> R
+    {
+        #[cfg(feature = "debug-leakcheck")]
+        let _scope = crate::leakcheck::Scope::enter::<T>(&**inner);
+
+        f(TakeSlot { inner })
+    }
+
+    fn into_inner(this: Self) -> &'a mut ManuallyDrop<T> {
+        let this = ManuallyDrop::new(this);
+        unsafe { ptr::read(&this.inner) }
+    }
+
+    /// Moves the value out of the slot.
+    pub fn read(self) -> T
+        where T: Sized
+    {
+        #[cfg(feature = "debug-leakcheck")]
+        crate::leakcheck::slot_taken::<T>(&**self.inner);
+
+        unsafe { ManuallyDrop::take(Self::into_inner(self)) }
+    }
+
+    /// Moves the value out of the slot, into its owned form.
+    pub fn into_owned(self) -> T::Owned
+        where T: IntoOwned
+    {
+        #[cfg(feature = "debug-leakcheck")]
+        crate::leakcheck::slot_taken::<T>(&**self.inner);
+
+        unsafe { T::into_owned_unchecked(Self::into_inner(self)) }
+    }
+
+    /// Returns the underlying `ManuallyDrop<T>`, for taking the value by other means.
+    ///
+    /// Like the slot, the returned value is leaked unless it's moved out of. This is an associated
+    /// function so it doesn't shadow methods of `T`.
+    pub fn into_raw(this: Self) -> &'a mut ManuallyDrop<T> {
+        #[cfg(feature = "debug-leakcheck")]
+        crate::leakcheck::slot_released::<T>(&**this.inner);
+
+        Self::into_inner(this)
+    }
+
+    /// Returns a raw pointer to the value.
+    pub fn as_ptr(this: &Self) -> *const T {
+        &**this.inner
+    }
+
+    /// Returns a mutable raw pointer to the value.
+    pub fn as_mut_ptr(this: &mut Self) -> *mut T {
+        &mut **this.inner
+    }
+}
+
+#[cfg(feature = "debug-leakcheck")]
+impl<T: ?Sized> Drop for TakeSlot<'_, T> {
+    fn drop(&mut self) {
+        crate::leakcheck::slot_released::<T>(&**self.inner);
+    }
+}
+
+impl<T: ?Sized> ops::Deref for TakeSlot<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.inner
+    }
+}
+
+impl<T: ?Sized> ops::DerefMut for TakeSlot<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.inner
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for TakeSlot<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("TakeSlot").field(&&**self).finish()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use alloc::{boxed::Box, rc::Rc, string::String};
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_read() {
+        let check = DropCheck::new();
+
+        let (token, state) = check.pair();
+        let token = Box::new(token).deref_take_unsized(|slot| slot.read());
+        assert!(state.is_not_dropped());
+        drop(token);
+        assert!(state.is_dropped());
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let boxed: Box<str> = "hello".into();
+        let s: String = Take::<str>::take_unsized(boxed, |mut slot| {
+            slot.make_ascii_uppercase();
+            slot.into_owned()
+        });
+        assert_eq!(s, "HELLO");
+    }
+
+    #[test]
+    fn test_unconsumed() {
+        let rc = Rc::new(());
+        Box::new(Rc::clone(&rc)).deref_take_unsized(|_| ());
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        // Release the leaked clone, so Miri's leak check passes.
+        unsafe { Rc::decrement_strong_count(Rc::as_ptr(&rc)) }
+    }
+}
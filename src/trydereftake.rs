@@ -0,0 +1,161 @@
+use core::mem::ManuallyDrop;
+use core::ops;
+
+use alloc::{
+    rc::Rc,
+    sync::Arc,
+};
+
+use super::*;
+
+/// Fallible `DerefTake`, for containers that can't always give up ownership.
+///
+/// Unlike `DerefTake`, no `Clone` bound is needed: if ownership can't be taken the container is
+/// returned unchanged.
+///
+/// # Safety
+///
+/// This trait is unsafe to implement because `try_deref_take_unsized()` must not `drop()` or
+/// otherwise use the taken value after the closure returns.
+pub unsafe trait TryDerefTake : ops::Deref + Sized {
+    /// Tries to take ownership, returning the container if that isn't possible.
+    fn try_deref_take(self) -> Result<<Self::Target as IntoOwned>::Owned, Self>
+        where Self::Target: IntoOwned
+    {
+        self.try_deref_take_unsized(|src| src.into_owned())
+    }
+
+    /// Takes ownership if possible, and otherwise builds the owned value with `fallback`.
+    ///
+    /// For shared `Rc`s and `Arc`s this is an alternative to `DerefTake`'s cloning, without the
+    /// `Clone` bound.
+    fn deref_take_or_else<F>(self, fallback: F) -> <Self::Target as IntoOwned>::Owned
+        where Self::Target: IntoOwned,
+              F: FnOnce(&Self::Target) -> <Self::Target as IntoOwned>::Owned
+    {
+        self.try_deref_take().unwrap_or_else(|this| fallback(&this))
+    }
+
+    /// Tries to take ownership of an unsized type with the aid of a closure.
+    ///
+    /// If ownership can be taken, the closure is called with a `TakeSlot<T>`; after the closure
+    /// returns the memory occupied by the value will be deallocated, but `drop()` will *not* be
+    /// called on the value itself. Otherwise the closure is not called and the container is
+    /// returned.
+    fn try_deref_take_unsized<F, R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R;
+}
+
+unsafe impl<T: ?Sized> TryDerefTake for Rc<T> {
+    fn try_deref_take_unsized<F, R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        // Convert the Rc so that drop won't be called on the contents
+        let mut this: Rc<ManuallyDrop<T>> = unsafe { Rc::from_raw(Rc::into_raw(self) as *const _) };
+
+        // get_mut() only succeeds if there are no other strong or weak references.
+        match Rc::get_mut(&mut this) {
+            Some(unique) => Ok(unsafe { TakeSlot::scoped(unique, f) }),
+            None => Err(unsafe { Rc::from_raw(Rc::into_raw(this) as *const T) }),
+        }
+    }
+}
+
+unsafe impl<T: ?Sized> TryDerefTake for Arc<T> {
+    fn try_deref_take_unsized<F, R>(self, f: F) -> Result<R, Self>
+        where F: FnOnce(TakeSlot<'_, Self::Target>) -> R
+    {
+        let mut this: Arc<ManuallyDrop<T>> = unsafe { Arc::from_raw(Arc::into_raw(self) as *const _) };
+
+        match Arc::get_mut(&mut this) {
+            Some(unique) => Ok(unsafe { TakeSlot::scoped(unique, f) }),
+            None => Err(unsafe { Arc::from_raw(Arc::into_raw(this) as *const T) }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use dropcheck::DropCheck;
+
+    #[test]
+    fn test_rc() {
+        let check = DropCheck::new();
+
+        let (t1, s1) = check.pair();
+        let rc1 = Rc::new(t1);
+        let t1 = rc1.try_deref_take().ok().unwrap();
+        assert!(s1.is_not_dropped());
+        drop(t1);
+        assert!(s1.is_dropped());
+
+        let (t1, s1) = check.pair();
+        let rc1 = Rc::new(t1);
+        let rc2 = Rc::clone(&rc1);
+        let rc1 = rc1.try_deref_take().err().unwrap();
+        assert!(s1.is_not_dropped());
+        drop(rc2);
+
+        // unique again
+        let t1 = rc1.try_deref_take().ok().unwrap();
+        assert!(s1.is_not_dropped());
+        drop(t1);
+        assert!(s1.is_dropped());
+
+        // a weak reference also prevents taking
+        let rc1 = Rc::new(check.token());
+        let weak = Rc::downgrade(&rc1);
+        let rc1 = rc1.try_deref_take().err().unwrap();
+        drop(weak);
+        assert!(rc1.try_deref_take().is_ok());
+    }
+
+    #[test]
+    fn test_arc() {
+        let check = DropCheck::new();
+
+        let (t1, s1) = check.pair();
+        let arc1 = Arc::new(t1);
+        let arc2 = Arc::clone(&arc1);
+        let arc1 = arc1.try_deref_take().err().unwrap();
+        drop(arc2);
+
+        let t1 = arc1.try_deref_take().ok().unwrap();
+        assert!(s1.is_not_dropped());
+        drop(t1);
+        assert!(s1.is_dropped());
+    }
+
+    #[test]
+    fn test_deref_take_or_else() {
+        let check = DropCheck::new();
+
+        // DropToken isn't Clone, so DerefTake isn't available.
+        let (t1, s1) = check.pair();
+        let rc1 = Rc::new(t1);
+        let rc2 = Rc::clone(&rc1);
+        let fresh = rc1.deref_take_or_else(|_| check.token());
+        assert!(s1.is_not_dropped());
+        let t1 = rc2.deref_take_or_else(|_| unreachable!());
+        drop((fresh, t1));
+        assert!(check.all_dropped());
+
+        let arc: Arc<str> = Arc::from("shared");
+        let _arc2 = Arc::clone(&arc);
+        assert_eq!(arc.deref_take_or_else(|s| s.to_uppercase()), "SHARED");
+    }
+
+    #[test]
+    fn test_unsized() {
+        let rc: Rc<str> = Rc::from("hello");
+        let rc2 = Rc::clone(&rc);
+        let rc = rc.try_deref_take_unsized(|s| s.len()).err().unwrap();
+        drop(rc2);
+        assert_eq!(rc.try_deref_take_unsized(|s| s.len()).ok(), Some(5));
+
+        let arc: Arc<[u8]> = Arc::from(&b"abc"[..]);
+        assert_eq!(arc.try_deref_take().ok(), Some(vec![b'a', b'b', b'c']));
+    }
+}
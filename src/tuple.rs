@@ -0,0 +1,87 @@
+use core::mem::ManuallyDrop;
+
+use super::*;
+
+/// Taking ownership of a tuple of values from a tuple of containers.
+///
+/// `Take<(A, B)>` can't be implemented for `(TA, TB)`, as it would overlap with the blanket
+/// `Take<T> for T`, so tuples get their own trait. Implemented for tuples of up to eight elements.
+///
+/// # Safety
+///
+/// Same as `Take`, for every element.
+pub unsafe trait TakeTuple<T> : Sized {
+    /// Takes ownership of every element.
+    ///
+    /// The elements are taken in order. If taking one panics, those already taken are dropped.
+    fn take_tuple(self) -> T;
+
+    /// Takes ownership of every element with the aid of a closure.
+    ///
+    /// Like `Take::take_unsized()`, with the elements taken first as per `take_tuple()`.
+    fn take_tuple_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, T>) -> R
+    {
+        let mut tuple = ManuallyDrop::new(self.take_tuple());
+        unsafe { TakeSlot::scoped(&mut tuple, f) }
+    }
+}
+
+macro_rules! impl_take_tuple {
+    ($($t:ident $c:ident $i:tt),+) => {
+        unsafe impl<$($t, $c: Take<$t>),+> TakeTuple<($($t,)+)> for ($($c,)+) {
+            fn take_tuple(self) -> ($($t,)+) {
+                ($(self.$i.take_sized(),)+)
+            }
+        }
+    };
+}
+
+impl_take_tuple!(A TA 0);
+impl_take_tuple!(A TA 0, B TB 1);
+impl_take_tuple!(A TA 0, B TB 1, C TC 2);
+impl_take_tuple!(A TA 0, B TB 1, C TC 2, D TD 3);
+impl_take_tuple!(A TA 0, B TB 1, C TC 2, D TD 3, E TE 4);
+impl_take_tuple!(A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, G TG 5);
+impl_take_tuple!(A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, G TG 5, H TH 6);
+impl_take_tuple!(A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, G TG 5, H TH 6, I TI 7);
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    use alloc::{boxed::Box, string::String};
+
+    use dropcheck::{DropCheck, DropToken};
+
+    #[test]
+    fn test_take_tuple() {
+        let check = DropCheck::new();
+
+        let src = (Box::new(1u8), ManuallyDrop::new(String::from("two")), check.token());
+        let (a, b, token): (u8, String, _) = src.take_tuple();
+        assert_eq!((a, &*b), (1, "two"));
+        assert!(check.none_dropped());
+        drop(token);
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_take_tuple_unsized() {
+        let r = TakeTuple::<(u32, u32)>::take_tuple_unsized((Box::new(1u32), 2u32), |slot| slot.0 + slot.1);
+        assert_eq!(r, 3);
+    }
+
+    #[test]
+    fn test_panic() {
+        let check = DropCheck::new();
+
+        let once = TakeOnce::new(check.token());
+        let _ = once.try_take();
+        let r = std::panic::catch_unwind(|| {
+            let _: (DropToken, DropToken) = (Box::new(check.token()), once).take_tuple();
+        });
+        assert!(r.is_err());
+        assert!(check.all_dropped());
+    }
+}
@@ -0,0 +1,187 @@
+//! Taking the initialized prefix of a partially filled buffer.
+//!
+//! ```
+//! use core::mem::MaybeUninit;
+//!
+//! use owned::uninit::InitPrefix;
+//! use owned::DerefTake;
+//!
+//! let mut buf: Box<[MaybeUninit<String>]> = Box::new_uninit_slice(4);
+//! buf[0].write(String::from("a"));
+//! buf[1].write(String::from("b"));
+//!
+//! let filled = unsafe { InitPrefix::new(buf, 2) };
+//! assert_eq!(filled.deref_take(), ["a", "b"]);
+//! ```
+
+use core::fmt;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops;
+use core::ptr;
+use core::slice;
+
+use alloc::{boxed::Box, vec::Vec};
+
+use super::*;
+
+/// Takes the first `len` elements of a buffer, reusing its allocation.
+///
+/// The rest of the buffer becomes the spare capacity of the `Vec`.
+///
+/// # Safety
+///
+/// The first `len` elements must be initialized.
+///
+/// # Panics
+///
+/// Panics if `len` is greater than the length of the buffer.
+pub unsafe fn take_init_prefix<T>(buf: Box<[MaybeUninit<T>]>, len: usize) -> Vec<T> {
+    assert!(len <= buf.len(), "prefix length {} out of range for buffer of length {}", len, buf.len());
+
+    let mut buf = ManuallyDrop::new(buf.into_vec());
+    Vec::from_raw_parts(buf.as_mut_ptr() as *mut T, len, buf.capacity())
+}
+
+/// A buffer whose first `len` elements are initialized.
+///
+/// Derefs to the initialized elements, which are dropped along with the buffer, or taken via
+/// `DerefTake` and `Take`.
+pub struct InitPrefix<T> {
+    buf: ManuallyDrop<Box<[MaybeUninit<T>]>>,
+    len: usize,
+}
+
+impl<T> InitPrefix<T> {
+    /// Creates a new `InitPrefix`.
+    ///
+    /// # Safety
+    ///
+    /// The first `len` elements must be initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is greater than the length of the buffer.
+    pub unsafe fn new(buf: Box<[MaybeUninit<T>]>, len: usize) -> Self {
+        assert!(len <= buf.len(), "prefix length {} out of range for buffer of length {}", len, buf.len());
+        InitPrefix { buf: ManuallyDrop::new(buf), len }
+    }
+
+    /// Consumes the `InitPrefix`, returning the buffer and the initialized length.
+    ///
+    /// The initialized elements are not dropped.
+    pub fn into_raw(this: Self) -> (Box<[MaybeUninit<T>]>, usize) {
+        let mut this = ManuallyDrop::new(this);
+        (unsafe { ManuallyDrop::take(&mut this.buf) }, this.len)
+    }
+}
+
+impl<T> ops::Deref for InitPrefix<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len) }
+    }
+}
+
+impl<T> ops::DerefMut for InitPrefix<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut T, self.len) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for InitPrefix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("InitPrefix").field(&&**self).finish()
+    }
+}
+
+impl<T> Drop for InitPrefix<T> {
+    fn drop(&mut self) {
+        // Take the buffer first, so it's freed even if dropping an element panics. Moving the Box
+        // invalidates pointers derived from it earlier, so the slice is derived from it afterwards.
+        let mut buf = unsafe { ManuallyDrop::take(&mut self.buf) };
+        let init = ptr::slice_from_raw_parts_mut(buf.as_mut_ptr() as *mut T, self.len);
+        unsafe { ptr::drop_in_place(init) }
+    }
+}
+
+/// The deallocation info is the length of the whole buffer.
+unsafe impl<T> RawDerefTake for InitPrefix<T> {
+    type DeallocInfo = usize;
+
+    fn into_raw_parts(self) -> (*mut [T], usize) {
+        let (buf, len) = InitPrefix::into_raw(self);
+        let buf_len = buf.len();
+        let ptr = Box::into_raw(buf) as *mut T;
+        (ptr::slice_from_raw_parts_mut(ptr, len), buf_len)
+    }
+
+    unsafe fn dealloc(ptr: *mut [T], buf_len: usize) {
+        let buf = ptr::slice_from_raw_parts_mut(ptr as *mut MaybeUninit<T>, buf_len);
+        drop(Box::from_raw(buf))
+    }
+
+    fn raw_deref_take(self) -> <[T] as IntoOwned>::Owned {
+        let (buf, len) = InitPrefix::into_raw(self);
+        unsafe { take_init_prefix(buf, len) }
+    }
+}
+
+unsafe impl<T> Take<[T]> for InitPrefix<T> {
+    fn take_unsized<F,R>(self, f: F) -> R
+        where F: FnOnce(TakeSlot<'_, [T]>) -> R
+    {
+        self.deref_take_unsized(f)
+    }
+}
+
+crate::takeorclone::impl_take_or_clone!([T] InitPrefix<T> => [T]);
+crate::fallible::impl_try_take!([T] InitPrefix<T> => [T]);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    fn buffer(check: &DropCheck, len: usize, init: usize) -> Box<[MaybeUninit<DropToken>]> {
+        let mut buf: Box<[MaybeUninit<DropToken>]> = (0 .. len).map(|_| MaybeUninit::uninit()).collect();
+        for slot in &mut buf[.. init] {
+            slot.write(check.token());
+        }
+        buf
+    }
+
+    #[test]
+    fn test_take_init_prefix() {
+        let check = DropCheck::new();
+
+        let buf = buffer(&check, 4, 2);
+        let ptr = buf.as_ptr() as *const DropToken;
+        let v = unsafe { take_init_prefix(buf, 2) };
+        assert_eq!((v.len(), v.capacity(), v.as_ptr()), (2, 4, ptr));
+        assert!(check.none_dropped());
+        drop(v);
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_init_prefix() {
+        let check = DropCheck::new();
+
+        drop(unsafe { InitPrefix::new(buffer(&check, 3, 3), 3) });
+        assert!(check.all_dropped());
+
+        let filled = unsafe { InitPrefix::new(buffer(&check, 3, 1), 1) };
+        let n = filled.deref_take_unsized(|src| src.into_owned().len());
+        assert_eq!(n, 1);
+        assert!(check.all_dropped());
+
+        let buf = buffer(&check, 4, 2);
+        let ptr = buf.as_ptr() as *const DropToken;
+        let v = unsafe { InitPrefix::new(buf, 2) }.deref_take();
+        assert_eq!((v.len(), v.capacity(), v.as_ptr()), (2, 4, ptr));
+        drop(v);
+        assert!(check.all_dropped());
+    }
+}
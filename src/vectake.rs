@@ -0,0 +1,240 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ptr;
+
+use alloc::vec::Vec;
+
+use super::*;
+
+/// Element-wise taking for `Vec`.
+pub trait VecTakeExt<T> : Sized {
+    /// Moves the elements out, mapping each one with `f`.
+    ///
+    /// If `T` and `U` have the same size and alignment the allocation is guaranteed to be reused,
+    /// with each element written back in place. Otherwise a new `Vec` is allocated.
+    ///
+    /// If `f` panics the elements that weren't taken yet are dropped, as are the results produced
+    /// so far, and the panic is propagated.
+    fn take_map<U, F>(self, f: F) -> Vec<U>
+        where F: FnMut(T) -> U;
+
+    /// Takes the value out of each element, such as turning a `Vec<Box<U>>` into a `Vec<U>`.
+    ///
+    /// As with `take_map()`, the allocation is reused if the layouts match, and on panic
+    /// everything is dropped.
+    fn take_unbox_all<U>(self) -> Vec<U>
+        where T: Take<U>
+    {
+        self.take_map(Take::take_sized)
+    }
+
+    /// Tries to take the value out of each element, such as turning a `Vec<Rc<U>>` into a
+    /// `Vec<U>`.
+    ///
+    /// Every element is tried. If any fail, the error holds the results for all of them, from
+    /// which the failed indices can be found.
+    fn try_take_all<U>(self) -> Result<Vec<U>, TryTakeAllError<U, T::Error>>
+        where T: TryTake<U>;
+}
+
+/// The error returned by `VecTakeExt::try_take_all()`.
+pub struct TryTakeAllError<T, E> {
+    results: Vec<Result<T, E>>,
+}
+
+impl<T, E> TryTakeAllError<T, E> {
+    /// Returns the indices of the elements that couldn't be taken.
+    pub fn failed_indices(&self) -> Vec<usize> {
+        self.results.iter()
+            .enumerate()
+            .filter_map(|(i, r)| r.is_err().then_some(i))
+            .collect()
+    }
+
+    /// Returns the result for every element.
+    pub fn into_results(self) -> Vec<Result<T, E>> {
+        self.results
+    }
+}
+
+impl<T, E> fmt::Debug for TryTakeAllError<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TryTakeAllError").field("failed_indices", &self.failed_indices()).finish()
+    }
+}
+
+impl<T, E> fmt::Display for TryTakeAllError<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let failed = self.results.iter().filter(|r| r.is_err()).count();
+        write!(f, "failed to take {} of {} elements", failed, self.results.len())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, E> std::error::Error for TryTakeAllError<T, E> {}
+
+impl<T> VecTakeExt<T> for Vec<T> {
+    fn take_map<U, F>(self, mut f: F) -> Vec<U>
+        where F: FnMut(T) -> U
+    {
+        if mem::size_of::<T>() != mem::size_of::<U>() || mem::align_of::<T>() != mem::align_of::<U>() {
+            return self.into_iter().map(f).collect();
+        }
+
+        let mut src = ManuallyDrop::new(self);
+        let mut guard = Guard {
+            ptr: src.as_mut_ptr(),
+            len: src.len(),
+            capacity: src.capacity(),
+            done: 0,
+            _marker: PhantomData::<U>,
+        };
+
+        while guard.done < guard.len {
+            unsafe {
+                let slot = guard.ptr.add(guard.done);
+                let item = slot.read();
+                // The element at `done` has been moved out, so on panic the guard skips it.
+                let mapped = f(item);
+                (slot as *mut U).write(mapped);
+            }
+            guard.done += 1;
+        }
+
+        let guard = ManuallyDrop::new(guard);
+        unsafe { Vec::from_raw_parts(guard.ptr as *mut U, guard.len, guard.capacity) }
+    }
+
+    fn try_take_all<U>(self) -> Result<Vec<U>, TryTakeAllError<U, T::Error>>
+        where T: TryTake<U>
+    {
+        let results = self.take_map(TryTake::try_take_sized);
+        if results.iter().all(Result::is_ok) {
+            Ok(results.take_map(|r| match r {
+                Ok(taken) => taken,
+                Err(_) => unreachable!(),
+            }))
+        } else {
+            Err(TryTakeAllError { results })
+        }
+    }
+}
+
+/// Cleans up a partially mapped buffer if `f` panics.
+struct Guard<T, U> {
+    ptr: *mut T,
+    len: usize,
+    capacity: usize,
+    done: usize,
+    _marker: PhantomData<U>,
+}
+
+impl<T, U> Drop for Guard<T, U> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr as *mut U, self.done));
+
+            let rest = self.done + 1;
+            if rest < self.len {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.add(rest), self.len - rest));
+            }
+
+            // Frees the memory without dropping anything.
+            drop(Vec::from_raw_parts(self.ptr, 0, self.capacity));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::panic;
+
+    use dropcheck::{DropCheck, DropToken};
+
+    #[test]
+    fn test_in_place() {
+        let v: Vec<u32> = (0 .. 100).collect();
+        let ptr = v.as_ptr() as usize;
+        let v: Vec<i32> = v.take_map(|x| -(x as i32));
+        assert_eq!(v.as_ptr() as usize, ptr);
+        assert_eq!(v[99], -99);
+    }
+
+    #[test]
+    fn test_different_layout() {
+        let v = vec![1u8, 2, 3];
+        let v: Vec<u64> = v.take_map(u64::from);
+        assert_eq!(v, [1, 2, 3]);
+
+        let v: Vec<()> = Vec::<u8>::new().take_map(|_| ());
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_unbox_all() {
+        let check = DropCheck::new();
+
+        let v: Vec<Box<DropToken>> = (0 .. 10).map(|_| Box::new(check.token())).collect();
+        let v: Vec<DropToken> = v.take_unbox_all();
+        assert_eq!(v.len(), 10);
+        assert!(check.none_dropped());
+        drop(v);
+        assert!(check.all_dropped());
+    }
+
+    #[test]
+    fn test_try_take_all() {
+        use std::rc::Rc;
+
+        let v: Vec<Rc<String>> = vec![Rc::new(String::from("a")), Rc::new(String::from("b"))];
+        assert_eq!(v.try_take_all::<String>().unwrap(), ["a", "b"]);
+
+        let shared = Rc::new(String::from("shared"));
+        let v = vec![Rc::new(String::from("a")), Rc::clone(&shared), Rc::new(String::from("c"))];
+        let err = v.try_take_all::<String>().unwrap_err();
+        assert_eq!(err.failed_indices(), [1]);
+
+        assert_eq!(err.to_string(), "failed to take 1 of 3 elements");
+
+        let results = err.into_results();
+        assert_eq!(results[0].as_ref().unwrap(), "a");
+        assert!(Rc::ptr_eq(results[1].as_ref().unwrap_err(), &shared));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_try_take_all_boxed_error() {
+        use std::error::Error;
+        use std::rc::Rc;
+
+        fn take_all(v: Vec<Rc<String>>) -> Result<Vec<String>, Box<dyn Error>> {
+            Ok(v.try_take_all()?)
+        }
+
+        let shared = Rc::new(String::from("shared"));
+        let err = take_all(vec![Rc::clone(&shared)]).unwrap_err();
+        assert_eq!(err.to_string(), "failed to take 1 of 1 elements");
+    }
+
+    #[test]
+    fn test_panic() {
+        let check = DropCheck::new();
+
+        let v: Vec<DropToken> = (0 .. 10).map(|_| check.token()).collect();
+        let mut n = 0;
+        let r = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            v.take_map(|token| {
+                n += 1;
+                if n == 5 {
+                    panic!();
+                }
+                (token,)
+            })
+        }));
+        assert!(r.is_err());
+        assert!(check.all_dropped());
+    }
+}